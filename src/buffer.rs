@@ -4,6 +4,8 @@ use std::os::raw::c_void;
 #[cfg(feature = "serde")]
 use serde::ser::{Serialize, Serializer};
 
+use crate::error::{Error, Result};
+use crate::private::Sealed;
 use crate::state::RawLua;
 use crate::types::ValueRef;
 
@@ -16,6 +18,25 @@ use crate::types::ValueRef;
 #[derive(Clone, Debug, PartialEq)]
 pub struct Buffer(pub(crate) ValueRef);
 
+/// A plain numeric type which values can be stored in a [`Buffer`] as raw bytes.
+///
+/// Any bit pattern is a valid value of these types. This trait is sealed and implemented for
+/// integer and floating point primitives.
+#[cfg_attr(docsrs, doc(cfg(feature = "luau")))]
+#[cfg_attr(not(feature = "luau"), allow(unused))]
+pub trait BufferElement: Copy + Sealed {}
+
+macro_rules! impl_buffer_element {
+    ($($t:ty),*) => {
+        $(
+            impl Sealed for $t {}
+            impl BufferElement for $t {}
+        )*
+    };
+}
+
+impl_buffer_element!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
 #[cfg_attr(not(feature = "luau"), allow(unused))]
 impl Buffer {
     /// Copies the buffer data into a new `Vec<u8>`.
@@ -47,6 +68,16 @@ impl Buffer {
         self.len() == 0
     }
 
+    /// Returns `true` if both buffers have the same contents.
+    ///
+    /// Unlike the `PartialEq` implementation, which compares buffer references, this compares the
+    /// underlying bytes.
+    pub fn content_eq(&self, other: &Buffer) -> bool {
+        let lua = self.0.lua.lock();
+        let other_lua = other.0.lua.lock();
+        self.as_slice(&lua) == other.as_slice(&other_lua)
+    }
+
     /// Reads given number of bytes from the buffer at the given offset.
     ///
     /// Offset is 0-based.
@@ -84,6 +115,88 @@ impl Buffer {
         data[offset..offset + bytes.len()].copy_from_slice(bytes);
     }
 
+    // Copies the buffer contents into `dst` (of the same size in bytes) and zeroes the buffer
+    pub(crate) fn drain_into<T: BufferElement>(&self, dst: Option<&mut [T]>) {
+        let lua = self.0.lua.lock();
+        let data = self.as_slice_mut(&lua);
+        if let Some(dst) = dst {
+            let size = std::mem::size_of_val(dst);
+            let dst = unsafe { std::slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u8, size) };
+            dst.copy_from_slice(data);
+        }
+        data.fill(0);
+    }
+
+    /// Calls a function f with the buffer contents viewed as a slice of `f32` numbers.
+    ///
+    /// No data is copied. The buffer length must be a multiple of 4 bytes, otherwise an error is
+    /// returned. Luau buffers are always suitably aligned for the view.
+    ///
+    /// The values are in native byte order, which matches `buffer.readf32` only on little-endian
+    /// targets. The Lua instance stays locked while `f` runs. Returns
+    /// [`Error::RecursiveMutCallback`] if the buffer is already viewed as a mutable slice.
+    pub fn with_f32_slice<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&[f32]) -> R,
+    {
+        unsafe { self.with_typed_slice(f) }
+    }
+
+    /// Calls a function f with the buffer contents viewed as a mutable slice of `f32` numbers.
+    ///
+    /// Returns [`Error::RecursiveMutCallback`] if the buffer is already viewed as a slice.
+    /// See [`Buffer::with_f32_slice`] for other details.
+    pub fn with_f32_slice_mut<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut [f32]) -> R,
+    {
+        unsafe { self.with_typed_slice_mut(f) }
+    }
+
+    /// Calls a function f with the buffer contents viewed as a slice of `i32` numbers.
+    ///
+    /// See [`Buffer::with_f32_slice`] for details.
+    pub fn with_i32_slice<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&[i32]) -> R,
+    {
+        unsafe { self.with_typed_slice(f) }
+    }
+
+    /// Calls a function f with the buffer contents viewed as a mutable slice of `i32` numbers.
+    ///
+    /// See [`Buffer::with_f32_slice_mut`] for details.
+    pub fn with_i32_slice_mut<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut [i32]) -> R,
+    {
+        unsafe { self.with_typed_slice_mut(f) }
+    }
+
+    // Safety: every bit pattern must be a valid `T`
+    unsafe fn with_typed_slice<T, R>(&self, f: impl FnOnce(&[T]) -> R) -> Result<R> {
+        let lua = self.0.lua.lock();
+        let data = self.as_slice(&lua);
+        check_typed_len::<T>(data.len())?;
+        let _borrow = TypedSliceBorrow::new(&lua, data.as_ptr(), false)?;
+        match data.align_to::<T>() {
+            ([], data, []) => Ok(f(data)),
+            _ => Err(Error::runtime("buffer data is not aligned")),
+        }
+    }
+
+    // Safety: every bit pattern must be a valid `T`
+    unsafe fn with_typed_slice_mut<T, R>(&self, f: impl FnOnce(&mut [T]) -> R) -> Result<R> {
+        let lua = self.0.lua.lock();
+        let data = self.as_slice_mut(&lua);
+        check_typed_len::<T>(data.len())?;
+        let _borrow = TypedSliceBorrow::new(&lua, data.as_ptr(), true)?;
+        match data.align_to_mut::<T>() {
+            ([], data, []) => Ok(f(data)),
+            _ => Err(Error::runtime("buffer data is not aligned")),
+        }
+    }
+
     /// Returns an adaptor implementing [`io::Read`], [`io::Write`] and [`io::Seek`] over the
     /// buffer.
     ///
@@ -92,6 +205,29 @@ impl Buffer {
         BufferCursor(self, 0)
     }
 
+    /// Encodes the buffer contents as a base64 string (standard alphabet, with padding).
+    #[cfg(feature = "serde")]
+    pub(crate) fn to_base64(&self) -> String {
+        const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+        let lua = self.0.lua.lock();
+        let data = self.as_slice(&lua);
+        let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+        for chunk in data.chunks(3) {
+            let n = ((chunk[0] as u32) << 16)
+                | ((*chunk.get(1).unwrap_or(&0) as u32) << 8)
+                | (*chunk.get(2).unwrap_or(&0) as u32);
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+                } else {
+                    out.push('=');
+                }
+            }
+        }
+        out
+    }
+
     pub(crate) fn as_slice(&self, lua: &RawLua) -> &[u8] {
         unsafe {
             let (buf, size) = self.as_raw_parts(lua);
@@ -131,6 +267,48 @@ impl Buffer {
     }
 }
 
+fn check_typed_len<T>(len: usize) -> Result<()> {
+    let size = std::mem::size_of::<T>();
+    if len % size != 0 {
+        let msg = format!("buffer length {len} is not a multiple of {size} bytes");
+        return Err(Error::runtime(msg));
+    }
+    Ok(())
+}
+
+// Tracks a typed slice view of the buffer data, so shared and mutable views never overlap
+struct TypedSliceBorrow<'a> {
+    lua: &'a RawLua,
+    ptr: *const u8,
+}
+
+impl<'a> TypedSliceBorrow<'a> {
+    fn new(lua: &'a RawLua, ptr: *const u8, mutable: bool) -> Result<Self> {
+        let borrows = unsafe { &mut (*lua.extra()).buffer_borrows };
+        let count = borrows.entry(ptr).or_insert(0);
+        match (*count, mutable) {
+            (0, true) => *count = -1,
+            (n, false) if n >= 0 => *count += 1,
+            _ => return Err(Error::RecursiveMutCallback),
+        }
+        Ok(TypedSliceBorrow { lua, ptr })
+    }
+}
+
+impl Drop for TypedSliceBorrow<'_> {
+    fn drop(&mut self) {
+        let borrows = unsafe { &mut (*self.lua.extra()).buffer_borrows };
+        if let Some(count) = borrows.get_mut(&self.ptr) {
+            match *count {
+                -1 | 1 => {
+                    borrows.remove(&self.ptr);
+                }
+                _ => *count -= 1,
+            }
+        }
+    }
+}
+
 struct BufferCursor(Buffer, usize);
 
 impl io::Read for BufferCursor {
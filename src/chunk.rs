@@ -1,13 +1,20 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ffi::CString;
 use std::io::Result as IoResult;
 use std::panic::Location;
 use std::path::{Path, PathBuf};
 use std::string::String as StdString;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
 
 use crate::error::{Error, Result};
 use crate::function::Function;
+use crate::multi::MultiValue;
 use crate::state::{Lua, WeakLua};
 use crate::table::Table;
 use crate::traits::{FromLuaMulti, IntoLua, IntoLuaMulti};
@@ -138,10 +145,103 @@ pub struct Chunk<'a> {
     pub(crate) lua: WeakLua,
     pub(crate) name: StdString,
     pub(crate) env: Result<Option<Table>>,
+    // Error from validating the chunk name, reported when loading the chunk
+    pub(crate) name_error: Option<Error>,
     pub(crate) mode: Option<ChunkMode>,
     pub(crate) source: IoResult<Cow<'a, [u8]>>,
     #[cfg(feature = "luau")]
     pub(crate) compiler: Option<Compiler>,
+    #[cfg(feature = "luau")]
+    pub(crate) retain_bytecode: bool,
+    pub(crate) source_map: Option<SourceMap>,
+}
+
+/// Maps lines of generated Lua code back to the original source.
+///
+/// Useful for languages that compile to Lua: when a source map is attached to a [`Chunk`],
+/// `chunkname:line` locations in error messages and tracebacks are translated to the original
+/// `file:line` locations.
+///
+/// Only lines with an explicit mapping are translated, other locations are left as is.
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap {
+    lines: BTreeMap<usize, (StdString, usize)>,
+}
+
+impl SourceMap {
+    /// Creates a new empty source map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps `generated_line` of the chunk to the `line` in the original `file`.
+    pub fn add_line(mut self, generated_line: usize, file: impl Into<StdString>, line: usize) -> Self {
+        self.lines.insert(generated_line, (file.into(), line));
+        self
+    }
+
+    /// Returns the original location of the `generated_line`, if mapped.
+    pub fn get(&self, generated_line: usize) -> Option<(&str, usize)> {
+        let (file, line) = self.lines.get(&generated_line)?;
+        Some((file, *line))
+    }
+
+    // Rewrites all `{source}:{line}` locations in the message.
+    //
+    // Mapped lines are replaced with the original location, other lines are reported against the
+    // `original` chunk source.
+    pub(crate) fn remap(&self, source: &str, original: &str, message: &str) -> StdString {
+        let pattern = format!("{source}:");
+        let mut result = StdString::with_capacity(message.len());
+        let mut rest = message;
+        while let Some(pos) = rest.find(&pattern) {
+            let after = &rest[pos + pattern.len()..];
+            let digits = after.bytes().take_while(u8::is_ascii_digit).count();
+            result.push_str(&rest[..pos]);
+            match (after[..digits].parse().ok()).and_then(|line| self.get(line)) {
+                Some((file, line)) => result.push_str(&format!("{file}:{line}")),
+                None => result.push_str(&format!("{original}:{}", &after[..digits])),
+            }
+            rest = &after[digits..];
+        }
+        result.push_str(rest);
+        result
+    }
+
+    // Returns the chunk source as it appears in error messages (`short_src`)
+    pub(crate) fn short_source(chunk_name: &str) -> StdString {
+        match chunk_name.as_bytes().first() {
+            Some(b'=' | b'@') => chunk_name[1..].to_string(),
+            _ => format!("[string \"{}\"]", chunk_name.lines().next().unwrap_or_default()),
+        }
+    }
+
+    // Returns a chunk name unique to this load, so locations of the chunk in error messages can
+    // be told apart from other chunks with the same name.
+    fn unique_chunk_name(chunk_name: &str) -> StdString {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        match chunk_name.as_bytes().first() {
+            Some(b'=' | b'@') => format!("{chunk_name}#{id}"),
+            _ => format!("={}#{id}", Self::short_source(chunk_name)),
+        }
+    }
+}
+
+/// Source maps of the live chunks, keyed by the unique chunk source (as it appears in error
+/// messages). Each entry holds the original chunk source and the map.
+pub(crate) type SourceMaps = Arc<Mutex<FxHashMap<StdString, (StdString, SourceMap)>>>;
+
+// Removes the source map of a chunk when its function is garbage collected
+struct SourceMapGuard {
+    maps: SourceMaps,
+    source: StdString,
+}
+
+impl Drop for SourceMapGuard {
+    fn drop(&mut self) {
+        self.maps.lock().remove(&self.source);
+    }
 }
 
 /// Represents chunk mode (text or binary).
@@ -392,6 +492,25 @@ impl Compiler {
     ///
     /// Returns [`Error::SyntaxError`] if the source code is invalid.
     pub fn compile(&self, source: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+        let bytecode = self.compile_bytecode(source.as_ref());
+        if bytecode.first() == Some(&0) {
+            // The rest of the bytecode is the error message starting with `:`
+            // See https://github.com/luau-lang/luau/blob/0.640/Compiler/src/Compiler.cpp#L4336
+            let message = StdString::from_utf8_lossy(&bytecode[2..]).into_owned();
+            return Err(Error::SyntaxError {
+                incomplete_input: message.ends_with("<eof>"),
+                message,
+                chunk_name: None,
+            });
+        }
+
+        Ok(bytecode)
+    }
+
+    // Compiles the `source` into bytecode, which encodes the syntax error (if any).
+    //
+    // Loading such bytecode reports the error, so the source doesn't need to be parsed again.
+    pub(crate) fn compile_bytecode(&self, source: &[u8]) -> Vec<u8> {
         use std::cell::RefCell;
         use std::ffi::CStr;
         use std::os::raw::{c_char, c_int};
@@ -465,7 +584,7 @@ impl Compiler {
             })
         }
 
-        let bytecode = unsafe {
+        unsafe {
             let mut options = ffi::lua_CompileOptions::default();
             options.optimizationLevel = self.optimization_level as c_int;
             options.debugLevel = self.debug_level as c_int;
@@ -484,20 +603,8 @@ impl Compiler {
                 }
             }
             options.disabledBuiltins = disabled_builtins_ptr;
-            ffi::luau_compile(source.as_ref(), options)
-        };
-
-        if bytecode.first() == Some(&0) {
-            // The rest of the bytecode is the error message starting with `:`
-            // See https://github.com/luau-lang/luau/blob/0.640/Compiler/src/Compiler.cpp#L4336
-            let message = StdString::from_utf8_lossy(&bytecode[2..]).into_owned();
-            return Err(Error::SyntaxError {
-                incomplete_input: message.ends_with("<eof>"),
-                message,
-            });
+            ffi::luau_compile(source, options)
         }
-
-        Ok(bytecode)
     }
 }
 
@@ -515,6 +622,97 @@ impl Chunk<'_> {
     /// - `=` - custom chunk name (when truncation is needed, the beginning of the name is kept)
     pub fn set_name(mut self, name: impl Into<StdString>) -> Self {
         self.name = name.into();
+        self.name_error = None;
+        self
+    }
+
+    /// Sets a literal name of this chunk, which is shown in error traces exactly as given.
+    ///
+    /// This is equivalent to calling [`Chunk::set_name`] with the `=` prefix prepended.
+    ///
+    /// Loading the chunk fails if the name is too long to be shown without truncation.
+    pub fn set_chunk_name_literal(self, name: impl AsRef<str>) -> Self {
+        self.set_prefixed_name('=', name.as_ref())
+    }
+
+    /// Sets a file name of this chunk, which is shown in error traces as a file path.
+    ///
+    /// This is equivalent to calling [`Chunk::set_name`] with the `@` prefix prepended.
+    ///
+    /// Loading the chunk fails if the name is too long to be shown without truncation.
+    pub fn set_chunk_name_file(self, path: impl AsRef<str>) -> Self {
+        self.set_prefixed_name('@', path.as_ref())
+    }
+
+    fn set_prefixed_name(mut self, prefix: char, name: &str) -> Self {
+        // Lua keeps at most `LUA_IDSIZE - 1` bytes (including the prefix) in `short_src`
+        const MAX_NAME_LEN: usize = if cfg!(feature = "luau") { 254 } else { 58 };
+
+        self.name_error = (name.len() > MAX_NAME_LEN).then(|| {
+            let len = name.len();
+            let msg = format!("chunk name is too long ({len} bytes, maximum is {MAX_NAME_LEN})");
+            Error::runtime(msg)
+        });
+        self.name = format!("{prefix}{name}");
+        self
+    }
+
+    /// Sets the environment of this chunk to a new table with the given variables, that falls
+    /// back to the current chunk environment (the globals by default) for everything else.
+    ///
+    /// Neither the globals nor the previous environment are modified, so this is a convenient way
+    /// to pass a few values into a chunk (e.g. for templates or configuration files).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mluau::{Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// # let lua = Lua::new();
+    /// let chunk = lua.load("return string.rep(name, count)");
+    /// let res: String = chunk.with_globals([("name", "a")]).with_globals([("count", 3)]).eval()?;
+    /// assert_eq!(res, "aaa");
+    /// assert!(lua.globals().get::<Option<String>>("name")?.is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_globals<K, V>(mut self, vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: IntoLua,
+        V: IntoLua,
+    {
+        let parent = match self.env {
+            Ok(env) => env,
+            Err(err) => {
+                self.env = Err(err);
+                return self;
+            }
+        };
+        self.env = (|| {
+            let lua = self.lua.lock();
+            let lua = lua.lua();
+            let env = lua.create_table()?;
+            for (k, v) in vars {
+                env.raw_set(k, v)?;
+            }
+            let parent = parent.unwrap_or_else(|| lua.globals());
+            env.set_metatable(Some(lua.create_table_from([("__index", parent)])?))?;
+            Ok(Some(env))
+        })();
+        self
+    }
+
+    /// Attaches a [`SourceMap`] to this chunk.
+    ///
+    /// Line numbers of this chunk in error messages and tracebacks will be translated to the
+    /// original source locations. It's recommended to use `=` or `@` prefixed chunk names
+    /// (see [`Chunk::set_name`]), as long names can be truncated by Lua.
+    ///
+    /// To tell the chunk apart from other chunks with the same name, it's loaded under a unique
+    /// name (the chunk name with a `#<id>` suffix), which is visible in debug information. The
+    /// source map is released when the loaded function is garbage collected.
+    pub fn set_source_map(mut self, map: SourceMap) -> Self {
+        self.source_map = Some(map);
         self
     }
 
@@ -563,6 +761,20 @@ impl Chunk<'_> {
         self
     }
 
+    /// Sets whether the loaded function keeps the bytecode of this chunk.
+    ///
+    /// When enabled, the bytecode is available via [`Function::bytecode`] for as long as the
+    /// function is alive. It's retained only if the chunk is binary or compiled using a
+    /// [`Compiler`] (set for the chunk or via [`Lua::set_compiler`]).
+    ///
+    /// Disabled by default.
+    #[cfg(any(feature = "luau", doc))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "luau")))]
+    pub fn set_retain_bytecode(mut self, enabled: bool) -> Self {
+        self.retain_bytecode = enabled;
+        self
+    }
+
     /// Execute this chunk of code.
     ///
     /// This is equivalent to calling the chunk function with no arguments and no return values.
@@ -570,6 +782,17 @@ impl Chunk<'_> {
         self.call(())
     }
 
+    /// Execute this chunk of code and return the wall-clock time the execution took.
+    ///
+    /// Only the call itself is measured; loading and compiling the chunk are excluded.
+    /// This is intended for quick triage of slow scripts rather than precise profiling.
+    pub fn exec_timed(self) -> Result<Duration> {
+        let func = self.into_function()?;
+        let start = Instant::now();
+        func.call::<()>(())?;
+        Ok(start.elapsed())
+    }
+
     /// Evaluate the chunk as either an expression or block.
     ///
     /// If the chunk can be parsed as an expression, this loads and executes the chunk and returns
@@ -589,6 +812,14 @@ impl Chunk<'_> {
         }
     }
 
+    /// Evaluate the chunk and return all resulting values.
+    ///
+    /// This is the same as [`Chunk::eval`] with [`MultiValue`] as the return type, which is
+    /// useful for REPLs that print every value an expression returns.
+    pub fn eval_all(self) -> Result<MultiValue> {
+        self.eval()
+    }
+
     /// Load the chunk function and call it with the given arguments.
     ///
     /// This is equivalent to `into_function` and calling the resulting function.
@@ -601,26 +832,75 @@ impl Chunk<'_> {
     /// This simply compiles the chunk without actually executing it.
     #[cfg_attr(not(feature = "luau"), allow(unused_mut))]
     pub fn into_function(mut self) -> Result<Function> {
+        if let Some(err) = self.name_error.take() {
+            return Err(err);
+        }
+
         #[cfg(feature = "luau")]
-        if self.compiler.is_some() {
-            // We don't need to compile source if no compiler set
-            self.compile();
+        {
+            self.use_default_compiler();
+            if self.compiler.is_some() {
+                // We don't need to compile source if no compiler set
+                self.compile();
+            }
+        }
+
+        let source_map = self.source_map.take();
+        let name = match source_map {
+            Some(_) => SourceMap::unique_chunk_name(&self.name),
+            None => self.name.clone(),
+        };
+        let lua = self.lua.lock();
+        let source = self.source?;
+        let func = lua.load_chunk(
+            Some(&Self::convert_name(name.clone())?),
+            self.env?.as_ref(),
+            self.mode,
+            source.as_ref(),
+        )?;
+        // Keep Luau bytecode to make it available via `Function::bytecode`
+        #[cfg(feature = "luau")]
+        if self.retain_bytecode && source.first().is_some_and(|&b| b < b'\t') {
+            unsafe { lua.set_function_bytecode(&func, &source)? };
         }
+        if let Some(map) = source_map {
+            Self::register_source_map(lua.lua(), &func, &name, &self.name, map)?;
+        }
+        Ok(func)
+    }
 
-        let name = Self::convert_name(self.name)?;
-        self.lua
-            .lock()
-            .load_chunk(Some(&name), self.env?.as_ref(), self.mode, self.source?.as_ref())
+    /// Load this chunk into a [`Function`] using the given table as its environment.
+    ///
+    /// This is a shortcut for [`Chunk::set_environment`] followed by [`Chunk::into_function`].
+    /// The environment is applied while loading the chunk, so no extra work is done afterwards.
+    pub fn into_function_with_env(self, env: Table) -> Result<Function> {
+        self.set_environment(env).into_function()
+    }
+
+    /// Sets the default compiler (see [`Lua::set_compiler`]) if no compiler set for this chunk.
+    #[cfg(feature = "luau")]
+    fn use_default_compiler(&mut self) {
+        if self.compiler.is_none() {
+            self.compiler = self.default_compiler();
+        }
+    }
+
+    #[cfg(feature = "luau")]
+    fn default_compiler(&self) -> Option<Compiler> {
+        unsafe { (*self.lua.lock().extra()).compiler.clone() }
     }
 
     /// Compiles the chunk and changes mode to binary.
     ///
-    /// It does nothing if the chunk is already binary or invalid.
+    /// It does nothing if the chunk is already binary. In Luau, invalid source is compiled into
+    /// bytecode that reports the syntax error when loaded, so it's parsed only once.
     fn compile(&mut self) {
         if let Ok(ref source) = self.source {
             if self.detect_mode() == ChunkMode::Text {
                 #[cfg(feature = "luau")]
-                if let Ok(data) = self.compiler.get_or_insert_with(Default::default).compile(source) {
+                {
+                    let compiler = self.compiler.get_or_insert_with(Default::default);
+                    let data = compiler.compile_bytecode(source);
                     self.source = Ok(Cow::Owned(data));
                     self.mode = Some(ChunkMode::Binary);
                 }
@@ -677,26 +957,69 @@ impl Chunk<'_> {
     }
 
     fn to_expression(&self) -> Result<Function> {
+        if let Some(err) = &self.name_error {
+            return Err(err.clone());
+        }
         // We assume that mode is Text
         let source = self.source.as_ref();
         let source = source.map_err(Error::runtime)?;
         let source = Self::expression_source(source);
         // We don't need to compile source if no compiler options set
         #[cfg(feature = "luau")]
-        let source = self
-            .compiler
-            .as_ref()
-            .map(|c| c.compile(&source))
-            .transpose()?
-            .unwrap_or(source);
-
-        let name = Self::convert_name(self.name.clone())?;
+        let source = match self.compiler.clone().or_else(|| self.default_compiler()) {
+            Some(compiler) => compiler.compile_bytecode(&source),
+            None => source,
+        };
+
+        let name = match self.source_map {
+            Some(_) => SourceMap::unique_chunk_name(&self.name),
+            None => self.name.clone(),
+        };
         let env = match &self.env {
             Ok(Some(env)) => Some(env),
             Ok(None) => None,
             Err(err) => return Err(err.clone()),
         };
-        self.lua.lock().load_chunk(Some(&name), env, None, &source)
+        let lua = self.lua.lock();
+        let func = lua.load_chunk(Some(&Self::convert_name(name.clone())?), env, None, &source)?;
+        #[cfg(feature = "luau")]
+        if self.retain_bytecode && source.first().is_some_and(|&b| b < b'\t') {
+            unsafe { lua.set_function_bytecode(&func, &source)? };
+        }
+        if let Some(map) = &self.source_map {
+            Self::register_source_map(lua.lua(), &func, &name, &self.name, map.clone())?;
+        }
+        Ok(func)
+    }
+
+    // Makes `map` active for the chunk loaded as `func` under the (unique) `name`, for as long as
+    // the function is alive.
+    fn register_source_map(
+        lua: &Lua,
+        func: &Function,
+        name: &str,
+        orig_name: &str,
+        map: SourceMap,
+    ) -> Result<()> {
+        const GUARDS_KEY: &str = "__mluau_source_map_guards";
+
+        let source = SourceMap::short_source(name);
+        let maps = unsafe { (*lua.lock().extra()).source_maps.clone() };
+        let orig_source = SourceMap::short_source(orig_name);
+        maps.lock().insert(source.clone(), (orig_source, map));
+        let guard = lua.create_any_userdata(SourceMapGuard { maps, source })?;
+
+        // The guard is dropped (removing the map) once the function is collected
+        let guards = match lua.named_registry_value::<Option<Table>>(GUARDS_KEY)? {
+            Some(guards) => guards,
+            None => {
+                let guards = lua.create_table()?;
+                guards.set_metatable(Some(lua.create_table_from([("__mode", "k")])?))?;
+                lua.set_named_registry_value(GUARDS_KEY, &guards)?;
+                guards
+            }
+        };
+        guards.raw_set(func, guard)
     }
 
     fn detect_mode(&self) -> ChunkMode {
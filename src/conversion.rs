@@ -683,6 +683,59 @@ impl FromLua for BString {
     }
 }
 
+#[cfg(feature = "bytes")]
+impl IntoLua for bytes::Bytes {
+    /// Converts into a Luau buffer (avoiding the string interner), or into a string on other
+    /// backends.
+    #[inline]
+    fn into_lua(self, lua: &Lua) -> Result<Value> {
+        #[cfg(feature = "luau")]
+        return Ok(Value::Buffer(lua.create_buffer(self)?));
+        #[cfg(not(feature = "luau"))]
+        return Ok(Value::String(lua.create_string(self)?));
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl FromLua for bytes::Bytes {
+    #[inline]
+    fn from_lua(value: Value, lua: &Lua) -> Result<Self> {
+        Ok(Vec::from(BString::from_lua(value, lua)?).into())
+    }
+
+    #[inline]
+    unsafe fn from_specified_stack(idx: c_int, lua: &RawLua, state: *mut ffi::lua_State) -> Result<Self> {
+        Ok(Vec::from(BString::from_specified_stack(idx, lua, state)?).into())
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl IntoLua for uuid::Uuid {
+    /// Converts into a lowercase hyphenated string (eg. `67e55044-10b1-426f-9247-bb680e5fe0c8`).
+    #[inline]
+    fn into_lua(self, lua: &Lua) -> Result<Value> {
+        let mut buf = uuid::Uuid::encode_buffer();
+        let s = self.hyphenated().encode_lower(&mut buf);
+        Ok(Value::String(lua.create_string(s)?))
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl FromLua for uuid::Uuid {
+    fn from_lua(value: Value, _: &Lua) -> Result<Self> {
+        let ty = value.type_name();
+        let Value::String(s) = value else {
+            return Err(Error::from_lua_conversion(
+                ty,
+                Self::type_name(),
+                "expected string".to_string(),
+            ));
+        };
+        uuid::Uuid::try_parse_ascii(&s.as_bytes())
+            .map_err(|err| Error::from_lua_conversion(ty, Self::type_name(), err.to_string()))
+    }
+}
+
 impl IntoLua for &BStr {
     #[inline]
     fn into_lua(self, lua: &Lua) -> Result<Value> {
@@ -399,3 +399,53 @@ impl std::ops::BitOrAssign for HookTriggers {
         *self = *self | rhs;
     }
 }
+
+/// A global hook previously installed with [`Lua::set_global_hook`].
+///
+/// Returned by [`Lua::set_global_hook`] when it replaces an existing hook, so that the previous
+/// hook can be chained from the new one or reinstalled later.
+///
+/// The hook is bound to the Lua instance it was installed in and can only be called with it.
+///
+/// [`Lua::set_global_hook`]: crate::Lua::set_global_hook
+#[cfg(not(feature = "luau"))]
+#[cfg_attr(docsrs, doc(cfg(not(feature = "luau"))))]
+pub struct GlobalHook {
+    pub(crate) lua: crate::state::WeakLua,
+    pub(crate) triggers: HookTriggers,
+    pub(crate) callback: crate::types::HookCallback,
+}
+
+// The callback is required to be `Send` and is only ever invoked while holding the lock of the
+// Lua instance it belongs to, so it's never called concurrently.
+#[cfg(all(feature = "send", not(feature = "luau")))]
+unsafe impl Send for GlobalHook {}
+
+#[cfg(not(feature = "luau"))]
+impl GlobalHook {
+    /// Returns the triggers this hook was installed with.
+    pub fn triggers(&self) -> HookTriggers {
+        self.triggers
+    }
+
+    /// Invokes the hook callback.
+    ///
+    /// Returns an error if `lua` is not the instance the hook was installed in.
+    pub fn call(&self, lua: &crate::Lua, debug: &Debug) -> crate::Result<crate::VmState> {
+        let guard = lua.lock();
+        if *guard.weak() != self.lua {
+            let msg = "global hook belongs to a different Lua instance";
+            return Err(crate::Error::runtime(msg));
+        }
+        (self.callback)(lua, debug)
+    }
+}
+
+#[cfg(not(feature = "luau"))]
+impl std::fmt::Debug for GlobalHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("GlobalHook")
+            .field("triggers", &self.triggers)
+            .finish()
+    }
+}
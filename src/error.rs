@@ -33,6 +33,10 @@ pub enum Error {
         /// This is useful for implementing REPLs as they can query the user for more input if this
         /// is set.
         incomplete_input: bool,
+        /// Name of the chunk that failed to load (as set by [`Chunk::set_name`]), if known.
+        ///
+        /// [`Chunk::set_name`]: crate::Chunk::set_name
+        chunk_name: Option<StdString>,
     },
     /// Lua runtime error, aka `LUA_ERRRUN`.
     ///
@@ -386,6 +390,28 @@ impl Error {
         }
     }
 
+    /// Returns the innermost Lua runtime error message wrapped by this error.
+    ///
+    /// Walks through callback, context and bad argument wrappers until a [`Error::RuntimeError`] is
+    /// found. The stack traceback appended by Lua (if any) is stripped from the message.
+    ///
+    /// Returns `None` if the chain does not end with a runtime error.
+    pub fn runtime_message(&self) -> Option<&str> {
+        match self {
+            Error::RuntimeError(msg) => {
+                let msg = match msg.find("\nstack traceback:") {
+                    Some(pos) => &msg[..pos],
+                    None => msg,
+                };
+                Some(msg)
+            }
+            Error::CallbackError { cause, .. }
+            | Error::WithContext { cause, .. }
+            | Error::BadArgument { cause, .. } => cause.runtime_message(),
+            _ => None,
+        }
+    }
+
     /// Returns the parent of this error.
     #[doc(hidden)]
     pub fn parent(&self) -> Option<&Error> {
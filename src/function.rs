@@ -15,12 +15,24 @@ use crate::util::{
 use crate::value::Value;
 use crate::WeakLua;
 
+// Registry key of the (weak-keyed) table with bytecode of loaded Luau functions
+#[cfg(any(feature = "luau", doc))]
+pub(crate) const FUNCTION_BYTECODE_KEY: *const std::os::raw::c_char = cstr!("__mlua_function_bytecode");
+
+// Registry key of the (weak-keyed) table with main chunk functions loaded by Luau
+#[cfg(feature = "luau")]
+pub(crate) const MAIN_CHUNKS_KEY: *const std::os::raw::c_char = cstr!("__mlua_main_chunks");
+
 /// Handle to an internal Lua function.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Function(pub(crate) ValueRef);
 
 /// Contains information about a function.
 ///
+/// It describes the function itself rather than an active call, so call-specific details
+/// (such as whether the call is a tail call) are not included. Use `Debug::is_tail_call`
+/// within a hook to inspect them.
+///
 /// Please refer to the [`Lua Debug Interface`] for more information.
 ///
 /// [`Lua Debug Interface`]: https://www.lua.org/manual/5.4/manual.html#4.7
@@ -301,14 +313,14 @@ impl Function {
         let state = lua.state();
         unsafe {
             let _sg = StackGuard::new(state);
-            assert_stack(state, 1);
+            assert_stack(state, 3);
 
             let mut ar: ffi::lua_Debug = mem::zeroed();
             lua.push_ref_at(&self.0, state);
             #[cfg(not(feature = "luau"))]
             let res = ffi::lua_getinfo(state, cstr!(">Sn"), &mut ar);
             #[cfg(feature = "luau")]
-            let res = ffi::lua_getinfo(state, -1, cstr!("sn"), &mut ar);
+            let res = ffi::lua_getinfo(state, -1, cstr!("sna"), &mut ar);
             mlua_assert!(res != 0, "lua_getinfo failed with `>Sn`");
 
             FunctionInfo {
@@ -320,7 +332,15 @@ impl Function {
                 },
                 #[cfg(feature = "luau")]
                 name_what: None,
+                #[cfg(not(feature = "luau"))]
                 what: ptr_to_str(ar.what).unwrap_or("main"),
+                // Luau reports main chunks as "Lua" functions, so we look them up in the registry
+                #[cfg(feature = "luau")]
+                what: match ptr_to_str(ar.what) {
+                    Some("Lua") if is_main_chunk(state) => "main",
+                    Some(what) => what,
+                    None => "main",
+                },
                 source: ptr_to_lossy_str(ar.source).map(|s| s.into_owned()),
                 #[cfg(not(feature = "luau"))]
                 short_src: ptr_to_lossy_str(ar.short_src.as_ptr()).map(|s| s.into_owned()),
@@ -374,6 +394,39 @@ impl Function {
         data
     }
 
+    /// Returns the Luau bytecode this function was loaded from.
+    ///
+    /// Bytecode is retained only for functions loaded from chunks with
+    /// [`Chunk::set_retain_bytecode`] enabled. Returns `None` for any other function, including
+    /// functions defined inside the chunk.
+    ///
+    /// The bytecode can be cached and loaded later using [`ChunkMode::Binary`].
+    ///
+    /// [`Chunk::set_retain_bytecode`]: crate::Chunk::set_retain_bytecode
+    /// [`ChunkMode::Binary`]: crate::ChunkMode::Binary
+    #[cfg(any(feature = "luau", doc))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "luau")))]
+    pub fn bytecode(&self) -> Option<Vec<u8>> {
+        let lua = self.0.lua.lock();
+        let state = lua.state();
+        unsafe {
+            let _sg = StackGuard::new(state);
+            assert_stack(state, 2);
+
+            if ffi::lua_getfield(state, ffi::LUA_REGISTRYINDEX, FUNCTION_BYTECODE_KEY) != ffi::LUA_TTABLE {
+                return None;
+            }
+            lua.push_ref_at(&self.0, state);
+            ffi::lua_rawget(state, -2);
+            let mut len = 0;
+            let data = ffi::lua_tolstring(state, -1, &mut len);
+            if data.is_null() {
+                return None;
+            }
+            Some(slice::from_raw_parts(data as *const u8, len).to_vec())
+        }
+    }
+
     /// Retrieves recorded coverage information about this Lua function including inner calls.
     ///
     /// This function takes a callback as an argument and calls it providing [`CoverageInfo`]
@@ -530,6 +583,52 @@ impl Function {
         }))
     }
 
+    /// Wraps a Rust function or closure together with a list of Lua upvalues, returning an opaque
+    /// type that implements [`IntoLua`] trait.
+    ///
+    /// The upvalues are stored in the Lua closure (instead of being captured by the Rust closure)
+    /// and passed to the function as a slice on every call. This is useful to share immutable
+    /// configuration between many native functions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mluau::{Function, Lua, Result, Value};
+    /// # fn main() -> Result<()> {
+    /// # let lua = Lua::new();
+    /// let config = lua.create_table_from([("factor", 10)])?;
+    /// let scale = Function::wrap_with_upvalues(
+    ///     |_, upvalues: &[Value], x: i64| {
+    ///         let factor: i64 = upvalues[0].as_table().unwrap().get("factor")?;
+    ///         Ok(x * factor)
+    ///     },
+    ///     vec![Value::Table(config)],
+    /// );
+    /// lua.globals().set("scale", scale)?;
+    /// assert_eq!(lua.load("scale(4)").eval::<i64>()?, 40);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn wrap_with_upvalues<F, A, R>(func: F, upvalues: Vec<Value>) -> impl IntoLua
+    where
+        F: Fn(&Lua, &[Value], A) -> Result<R> + MaybeSend + 'static,
+        A: FromLuaMulti,
+        R: IntoLuaMulti,
+    {
+        let nupvalues = upvalues.len() as c_int;
+        let callback: Callback = Box::new(move |lua, nargs| unsafe {
+            let state = lua.state();
+            let args = A::from_specified_stack_args(nargs, 1, None, lua, state)?;
+            let mut upvalues = Vec::with_capacity(nupvalues as usize);
+            for i in 0..nupvalues {
+                ffi::lua_pushvalue(state, ffi::lua_upvalueindex(i + 2));
+                upvalues.push(lua.pop_value_at(state)?);
+            }
+            func(lua.lua(), &upvalues, args)?.push_into_specified_stack_multi(lua, state)
+        });
+        WrappedFunctionWithUpvalues(callback, upvalues)
+    }
+
     /// Wraps a Rust function or closure, returning an opaque type that implements [`IntoLua`]
     /// trait.
     ///
@@ -568,6 +667,21 @@ impl Function {
     }
 }
 
+// Checks if the function on top of the stack was loaded as a main chunk.
+// Requires 2 free stack slots.
+#[cfg(feature = "luau")]
+unsafe fn is_main_chunk(state: *mut ffi::lua_State) -> bool {
+    if ffi::lua_getfield(state, ffi::LUA_REGISTRYINDEX, MAIN_CHUNKS_KEY) != ffi::LUA_TTABLE {
+        ffi::lua_pop(state, 1);
+        return false;
+    }
+    ffi::lua_pushvalue(state, -2);
+    ffi::lua_rawget(state, -2);
+    let res = ffi::lua_toboolean(state, -1) != 0;
+    ffi::lua_pop(state, 2);
+    res
+}
+
 impl IntoLua for WrappedFunction {
     #[inline]
     fn into_lua(self, lua: &Lua) -> Result<Value> {
@@ -575,6 +689,17 @@ impl IntoLua for WrappedFunction {
     }
 }
 
+struct WrappedFunctionWithUpvalues(Callback, Vec<Value>);
+
+impl IntoLua for WrappedFunctionWithUpvalues {
+    #[inline]
+    fn into_lua(self, lua: &Lua) -> Result<Value> {
+        let lua = lua.lock();
+        lua.create_callback_with_upvalues(self.0, &self.1)
+            .map(Value::Function)
+    }
+}
+
 impl LuaType for Function {
     const TYPE_ID: c_int = ffi::LUA_TFUNCTION;
 }
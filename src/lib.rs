@@ -91,36 +91,37 @@ pub mod prelude;
 pub use bstr::BString;
 pub use ffi::{self, lua_CFunction, lua_State};
 
-pub use crate::chunk::{AsChunk, Chunk, ChunkMode};
+pub use crate::chunk::{AsChunk, Chunk, ChunkMode, SourceMap};
 pub use crate::debug::{Debug, DebugEvent, DebugNames, DebugSource, DebugStack};
 pub use crate::error::{Error, ErrorContext, ExternalError, ExternalResult, Result};
 pub use crate::function::{Function, FunctionInfo};
-pub use crate::multi::{MultiValue, Variadic};
-pub use crate::state::{GCMode, Lua, LuaOptions, WeakLua};
+pub use crate::multi::{LuaResultTuple, MultiValue, StackArgs, Variadic};
+pub use crate::state::{GCMode, Lua, LuaBackend, LuaOptions, RefThreadStats, WeakLua};
 pub use crate::stdlib::StdLib;
 pub use crate::string::{BorrowedBytes, BorrowedStr, String};
-pub use crate::table::{Table, TablePairs, TablePairsOwned, TableSequence};
+pub use crate::table::{Table, TablePairs, TablePairsOwned, TableSequence, TableShape};
 pub use crate::thread::{ContinuationStatus, Thread, ThreadStatus};
 pub use crate::traits::{
     FromLua, FromLuaMulti, IntoLua, IntoLuaMulti, LuaNativeFn, LuaNativeFnMut, ObjectLike,
 };
 pub use crate::types::{
-    AppDataRef, AppDataRefMut, Either, Integer, LightUserData, MaybeSend, Number, RegistryKey, VmState,
+    AppDataRef, AppDataRefMut, Either, Integer, LightRef, LightUserData, MaybeSend, Number, RegistryKey,
+    VmState,
 };
 pub use crate::userdata::{
-    AnyUserData, MetaMethod, UserData, UserDataFields, UserDataMetatable, UserDataMethods, UserDataRef,
-    UserDataRefMut, UserDataRegistry,
+    AnyUserData, MetaMethod, UserData, UserDataDispatch, UserDataFields, UserDataMetatable, UserDataMethods,
+    UserDataRef, UserDataRefMut, UserDataRegistry,
 };
 
 pub use crate::value::{Nil, Value};
 
 #[cfg(not(feature = "luau"))]
-pub use crate::debug::HookTriggers;
+pub use crate::debug::{GlobalHook, HookTriggers};
 
 #[cfg(any(feature = "luau", doc))]
 #[cfg_attr(docsrs, doc(cfg(feature = "luau")))]
 pub use crate::{
-    buffer::Buffer,
+    buffer::{Buffer, BufferElement},
     chunk::{CompileConstant, Compiler},
     function::CoverageInfo,
     luau::{HeapDump, NavigateError, Require, TextRequirer},
@@ -14,6 +14,8 @@ use crate::types::MaybeSend;
 pub use heap_dump::HeapDump;
 pub use require::{NavigateError, Require, TextRequirer};
 
+pub(crate) use require::find_requires;
+
 #[cfg(feature = "luau")]
 static HAVE_SET_INTEGER_FFLAG: AtomicBool = AtomicBool::new(false);
 
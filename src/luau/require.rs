@@ -14,6 +14,7 @@ use crate::types::MaybeSend;
 
 // TODO: Rename to FsRequirer
 pub use fs::TextRequirer;
+pub(crate) use scan::find_requires;
 
 /// An error that can occur during navigation in the Luau `require-by-string` system.
 #[derive(Debug, Clone)]
@@ -469,3 +470,4 @@ pub(super) fn create_require_function<R: Require + MaybeSend + 'static>(
 }
 
 mod fs;
+mod scan;
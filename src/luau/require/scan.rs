@@ -0,0 +1,255 @@
+//! Static extraction of `require` calls from Luau source code.
+
+/// Returns the module paths passed as string literals to `require` calls in `source`.
+///
+/// The source is tokenized (skipping comments and strings) without being executed, and calls in
+/// the form of `require("path")`, `require "path"` or `require [[path]]` are collected in the
+/// order of appearance. Calls with non-literal arguments and method calls like `x.require(...)`
+/// are ignored.
+pub(crate) fn find_requires(source: &[u8]) -> Vec<String> {
+    let mut lexer = Lexer { src: source, pos: 0 };
+    let mut requires = Vec::new();
+    let mut prev = Token::Other;
+    while let Some(token) = lexer.next_token() {
+        if token == Token::Ident(b"require") && !matches!(prev, Token::Punct(b'.' | b':')) {
+            let mut next = lexer.next_token();
+            if next == Some(Token::Punct(b'(')) {
+                next = lexer.next_token();
+            }
+            if let Some(Token::Str(path)) = next {
+                requires.push(path);
+            }
+            prev = Token::Other;
+            continue;
+        }
+        prev = token;
+    }
+    requires
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Ident(&'a [u8]),
+    Str(String),
+    Punct(u8),
+    Other,
+}
+
+struct Lexer<'a> {
+    src: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn peek(&self, offset: usize) -> Option<u8> {
+        self.src.get(self.pos + offset).copied()
+    }
+
+    fn next_token(&mut self) -> Option<Token<'a>> {
+        loop {
+            let c = self.peek(0)?;
+            match c {
+                _ if c.is_ascii_whitespace() => self.pos += 1,
+                b'-' if self.peek(1) == Some(b'-') => {
+                    self.pos += 2;
+                    if self.long_bracket().is_none() {
+                        while !matches!(self.peek(0), None | Some(b'\n')) {
+                            self.pos += 1;
+                        }
+                    }
+                }
+                b'"' | b'\'' | b'`' => {
+                    // Interpolated strings are not literals
+                    return Some(self.quoted_string(c).map(Token::Str).unwrap_or(Token::Other));
+                }
+                b'[' if matches!(self.peek(1), Some(b'[' | b'=')) => {
+                    return match self.long_bracket() {
+                        Some(s) => Some(Token::Str(s)),
+                        None => {
+                            self.pos += 1;
+                            Some(Token::Punct(b'['))
+                        }
+                    };
+                }
+                _ if c.is_ascii_alphanumeric() || c == b'_' => {
+                    let start = self.pos;
+                    while matches!(self.peek(0), Some(c) if c.is_ascii_alphanumeric() || c == b'_') {
+                        self.pos += 1;
+                    }
+                    return Some(Token::Ident(&self.src[start..self.pos]));
+                }
+                _ => {
+                    self.pos += 1;
+                    return Some(Token::Punct(c));
+                }
+            }
+        }
+    }
+
+    // Reads a `[==[ ... ]==]` block starting at the current position
+    fn long_bracket(&mut self) -> Option<String> {
+        if self.peek(0) != Some(b'[') {
+            return None;
+        }
+        let mut level = 0;
+        while self.peek(level + 1) == Some(b'=') {
+            level += 1;
+        }
+        if self.peek(level + 1) != Some(b'[') {
+            return None;
+        }
+        let start = self.pos + level + 2;
+        let mut close = vec![b']'; level + 2];
+        close[1..=level].fill(b'=');
+        let rest = &self.src[start..];
+        let end = rest
+            .windows(close.len())
+            .position(|w| w == close)
+            .unwrap_or(rest.len());
+        self.pos = (start + end + close.len()).min(self.src.len());
+        let mut content = &rest[..end];
+        // The first newline is skipped
+        if let Some(stripped) = content
+            .strip_prefix(b"\r\n")
+            .or_else(|| content.strip_prefix(b"\n"))
+        {
+            content = stripped;
+        }
+        Some(String::from_utf8_lossy(content).into_owned())
+    }
+
+    // Reads a quoted string, returns `None` for backtick strings with interpolations
+    fn quoted_string(&mut self, quote: u8) -> Option<String> {
+        self.pos += 1;
+        let mut buf = Vec::new();
+        let mut interpolated = false;
+        while let Some(c) = self.peek(0) {
+            self.pos += 1;
+            match c {
+                _ if c == quote => break,
+                b'\n' if quote != b'`' => break, // unfinished string
+                b'{' if quote == b'`' => {
+                    interpolated = true;
+                    self.skip_interpolation();
+                }
+                b'\\' => self.escape_sequence(&mut buf),
+                _ => buf.push(c),
+            }
+        }
+        (!interpolated).then(|| String::from_utf8_lossy(&buf).into_owned())
+    }
+
+    // Skips an interpolated expression up to the matching `}`
+    fn skip_interpolation(&mut self) {
+        let mut depth = 1;
+        while let Some(token) = self.next_token() {
+            match token {
+                Token::Punct(b'{') => depth += 1,
+                Token::Punct(b'}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Decodes an escape sequence following a backslash
+    fn escape_sequence(&mut self, buf: &mut Vec<u8>) {
+        let Some(e) = self.peek(0) else { return };
+        self.pos += 1;
+        match e {
+            b'a' => buf.push(0x07),
+            b'b' => buf.push(0x08),
+            b'f' => buf.push(0x0c),
+            b'n' => buf.push(b'\n'),
+            b'r' => buf.push(b'\r'),
+            b't' => buf.push(b'\t'),
+            b'v' => buf.push(0x0b),
+            b'\r' => {
+                // `\r\n` is a single line break
+                if self.peek(0) == Some(b'\n') {
+                    self.pos += 1;
+                }
+                buf.push(b'\n');
+            }
+            b'z' => {
+                while matches!(self.peek(0), Some(c) if c.is_ascii_whitespace()) {
+                    self.pos += 1;
+                }
+            }
+            b'x' => {
+                let digits = self.take_while(2, |c| c.is_ascii_hexdigit());
+                buf.push(u8::from_str_radix(digits, 16).unwrap_or(0));
+            }
+            b'0'..=b'9' => {
+                self.pos -= 1;
+                let digits = self.take_while(3, |c| c.is_ascii_digit());
+                buf.push(digits.parse::<u16>().map_or(0, |n| n as u8));
+            }
+            b'u' if self.peek(0) == Some(b'{') => {
+                self.pos += 1;
+                let digits = self.take_while(8, |c| c.is_ascii_hexdigit());
+                if self.peek(0) == Some(b'}') {
+                    self.pos += 1;
+                }
+                let ch = u32::from_str_radix(digits, 16).ok().and_then(char::from_u32);
+                let mut utf8 = [0; 4];
+                buf.extend_from_slice(ch.unwrap_or('\u{fffd}').encode_utf8(&mut utf8).as_bytes());
+            }
+            // `\\`, quotes and escaped line breaks stand for themselves
+            _ => buf.push(e),
+        }
+    }
+
+    // Consumes up to `max` bytes matching `pred`
+    fn take_while(&mut self, max: usize, pred: impl Fn(u8) -> bool) -> &'a str {
+        let start = self.pos;
+        while self.pos - start < max && matches!(self.peek(0), Some(c) if pred(c)) {
+            self.pos += 1;
+        }
+        // Only ASCII digits are consumed
+        std::str::from_utf8(&self.src[start..self.pos]).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_requires;
+
+    #[test]
+    fn test_find_requires() {
+        let source = br#"
+            local a = require("./a")
+            local b = require './b' -- require("./commented")
+            local c = require [[./c]]
+            --[==[
+                require("./long_comment")
+            ]==]
+            local s = "require('./in_string')"
+            local d = obj.require("./method")
+            local e = require(path)
+            local f = require(`./f`)
+            local g = require(`./{name}`)
+            local h = require(`./{ {x = "}"} }/h`)
+        "#;
+        assert_eq!(find_requires(source), ["./a", "./b", "./c", "./f"]);
+    }
+
+    #[test]
+    fn test_find_requires_escapes() {
+        let source = br#"
+            require("\x41\66\u{43}\u{e9}")
+            require("a\z
+                     b")
+            require('\'\"\\\a\b\f\v')
+            require(`\{x\}`)
+        "#;
+        assert_eq!(
+            find_requires(source),
+            ["ABC\u{e9}", "ab", "'\"\\\x07\x08\x0c\x0b", "{x}"]
+        );
+    }
+}
@@ -9,6 +9,8 @@ pub(crate) static ALLOCATOR: ffi::lua_Alloc = allocator;
 pub(crate) struct MemoryState {
     used_memory: isize,
     memory_limit: isize,
+    // Total number of (new) allocations made since the state was created.
+    allocation_count: u64,
     // Can be set to temporary ignore the memory limit.
     // This is used when calling `lua_pushcfunction` for lua5.1/jit/luau.
     ignore_limit: bool,
@@ -55,6 +57,11 @@ impl MemoryState {
         self.used_memory as usize
     }
 
+    #[inline]
+    pub(crate) fn allocation_count(&self) -> u64 {
+        self.allocation_count
+    }
+
     #[inline]
     pub(crate) fn memory_limit(&self) -> usize {
         self.memory_limit as usize
@@ -151,6 +158,7 @@ unsafe extern "C" fn allocator(
         if new_ptr.is_null() {
             alloc::handle_alloc_error(new_layout);
         }
+        mem_state.allocation_count += 1;
         return new_ptr;
     }
 
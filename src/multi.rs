@@ -5,7 +5,7 @@ use std::ops::{Deref, DerefMut};
 use std::os::raw::c_int;
 use std::result::Result as StdResult;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::state::{Lua, RawLua};
 use crate::traits::{FromLua, FromLuaMulti, IntoLua, IntoLuaMulti};
 use crate::util::check_stack;
@@ -65,6 +65,11 @@ impl<T: IntoLua> IntoLuaMulti for T {
         Ok(v)
     }
 
+    #[inline]
+    fn expected_len() -> Option<usize> {
+        Some(1)
+    }
+
     #[inline]
     unsafe fn push_into_specified_stack_multi(
         self,
@@ -163,6 +168,15 @@ impl MultiValue {
         self.into()
     }
 
+    /// Splits off the first value, returning it together with the remaining values.
+    ///
+    /// Returns `None` if the `MultiValue` is empty. This method works in *O*(1) time.
+    #[inline]
+    pub fn split_first(mut self) -> Option<(Value, MultiValue)> {
+        let first = self.0.pop_front()?;
+        Some((first, self))
+    }
+
     #[inline]
     pub(crate) fn from_lua_iter<T: IntoLua>(lua: &Lua, iter: impl IntoIterator<Item = T>) -> Result<Self> {
         let iter = iter.into_iter();
@@ -364,6 +378,137 @@ impl<T: FromLua> FromLuaMulti for Variadic<T> {
             .collect::<Result<Vec<T>>>()
             .map(Variadic)
     }
+
+    // Report the position of each individual argument that failed to convert
+    #[inline]
+    fn from_lua_args(mut args: MultiValue, i: usize, to: Option<&str>, lua: &Lua) -> Result<Self> {
+        args.drain(..)
+            .enumerate()
+            .map(|(n, val)| T::from_lua_arg(val, i + n, to, lua))
+            .collect::<Result<Vec<T>>>()
+            .map(Variadic)
+    }
+
+    #[inline]
+    unsafe fn from_specified_stack_args(
+        nargs: c_int,
+        i: usize,
+        to: Option<&str>,
+        lua: &RawLua,
+        state: *mut ffi::lua_State,
+    ) -> Result<Self> {
+        (0..nargs)
+            .map(|n| T::from_specified_stack_arg(-nargs + n, i + n as usize, to, lua, state))
+            .collect::<Result<Vec<T>>>()
+            .map(Variadic)
+    }
+}
+
+/// Arguments of a Rust function call, left on the Lua stack and converted on demand.
+///
+/// This struct is passed to functions created by [`Lua::create_raw_function`].
+pub struct StackArgs<'a> {
+    lua: &'a RawLua,
+    state: *mut ffi::lua_State,
+    // Absolute stack index of the first argument
+    base: c_int,
+    nargs: c_int,
+}
+
+impl<'a> StackArgs<'a> {
+    /// Captures `nargs` values on top of the stack.
+    pub(crate) unsafe fn new(lua: &'a RawLua, state: *mut ffi::lua_State, nargs: c_int) -> Self {
+        let base = ffi::lua_gettop(state) - nargs + 1;
+        StackArgs {
+            lua,
+            state,
+            base,
+            nargs,
+        }
+    }
+
+    /// Returns the number of arguments passed to the function.
+    pub fn len(&self) -> usize {
+        self.nargs as usize
+    }
+
+    /// Returns `true` if no arguments were passed to the function.
+    pub fn is_empty(&self) -> bool {
+        self.nargs == 0
+    }
+
+    /// Converts the argument at (zero-based) position `i` to `T`.
+    ///
+    /// Missing arguments are converted from `nil`.
+    pub fn get<T: FromLua>(&self, i: usize) -> Result<T> {
+        if i >= self.len() {
+            return T::from_lua_arg(Nil, i + 1, None, self.lua.lua());
+        }
+        let idx = self.base + i as c_int;
+        unsafe { T::from_specified_stack_arg(idx, i + 1, None, self.lua, self.state) }
+    }
+
+    /// Converts all arguments to a [`MultiValue`].
+    pub fn to_multi_value(&self) -> Result<MultiValue> {
+        (0..self.len()).map(|i| self.get(i)).collect()
+    }
+}
+
+/// Wraps a [`Result`] to be returned to Lua as `value` on success, or `nil, message` on failure.
+///
+/// Unlike returning `Err` from a callback, the error is not raised. This allows a single function
+/// to choose per call whether to raise an error or follow the Lua `nil, err` idiom.
+///
+/// # Examples
+///
+/// ```
+/// # use mluau::{Error, Lua, LuaResultTuple, Result};
+/// # fn main() -> Result<()> {
+/// # let lua = Lua::new();
+/// let parse = lua.create_function(|_, s: String| {
+///     Ok(LuaResultTuple(s.parse::<i64>().map_err(Error::external)))
+/// })?;
+/// lua.globals().set("parse", parse)?;
+/// lua.load(r#"
+///     assert(parse("42") == 42)
+///     local ok, err = parse("abc")
+///     assert(ok == nil and type(err) == "string")
+/// "#).exec()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Result`]: std::result::Result
+#[derive(Debug, Clone)]
+pub struct LuaResultTuple<T, E = Error>(pub StdResult<T, E>);
+
+impl<T, E> From<StdResult<T, E>> for LuaResultTuple<T, E> {
+    #[inline]
+    fn from(res: StdResult<T, E>) -> Self {
+        LuaResultTuple(res)
+    }
+}
+
+impl<T: IntoLuaMulti, E: Into<Error>> IntoLuaMulti for LuaResultTuple<T, E> {
+    #[inline]
+    fn into_lua_multi(self, lua: &Lua) -> Result<MultiValue> {
+        match self.0 {
+            Ok(val) => val.into_lua_multi(lua),
+            Err(err) => (Nil, err.into().to_string()).into_lua_multi(lua),
+        }
+    }
+
+    #[inline]
+    unsafe fn push_into_specified_stack_multi(
+        self,
+        lua: &RawLua,
+        state: *mut ffi::lua_State,
+    ) -> Result<c_int> {
+        match self.0 {
+            Ok(val) => val.push_into_specified_stack_multi(lua, state),
+            Err(err) => (Nil, err.into().to_string()).push_into_specified_stack_multi(lua, state),
+        }
+    }
 }
 
 macro_rules! impl_tuple {
@@ -382,6 +527,11 @@ macro_rules! impl_tuple {
             ) -> Result<c_int> {
                 Ok(0)
             }
+
+            #[inline]
+            fn expected_len() -> Option<usize> {
+                Some(0)
+            }
         }
 
         impl FromLuaMulti for () {
@@ -428,6 +578,12 @@ macro_rules! impl_tuple {
                 nresults += $last.push_into_specified_stack_multi(lua, state)?;
                 Ok(nresults)
             }
+
+            #[inline]
+            fn expected_len() -> Option<usize> {
+                let len = <[&str]>::len(&[$(stringify!($name)),*]);
+                <$last as IntoLuaMulti>::expected_len().map(|n| n + len)
+            }
         }
 
         impl<$($name,)* $last> FromLuaMulti for ($($name,)* $last,)
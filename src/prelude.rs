@@ -6,11 +6,13 @@ pub use crate::{
     Chunk as LuaChunk, ContinuationStatus as LuaContinuationStatus, Either as LuaEither, Error as LuaError,
     ErrorContext as LuaErrorContext, ExternalError as LuaExternalError, ExternalResult as LuaExternalResult,
     FromLua, FromLuaMulti, Function as LuaFunction, FunctionInfo as LuaFunctionInfo, GCMode as LuaGCMode,
-    Integer as LuaInteger, IntoLua, IntoLuaMulti, LightUserData as LuaLightUserData, Lua, LuaNativeFn,
-    LuaNativeFnMut, LuaOptions, MetaMethod as LuaMetaMethod, MultiValue as LuaMultiValue, Nil as LuaNil,
-    Number as LuaNumber, ObjectLike as LuaObjectLike, RegistryKey as LuaRegistryKey, Result as LuaResult,
-    StdLib as LuaStdLib, String as LuaString, Table as LuaTable, TablePairs as LuaTablePairs,
-    TablePairsOwned as LuaTablePairsOwned, TableSequence as LuaTableSequence, Thread as LuaThread,
+    Integer as LuaInteger, IntoLua, IntoLuaMulti, LightRef as LuaLightRef, LightUserData as LuaLightUserData,
+    Lua, LuaBackend, LuaNativeFn, LuaNativeFnMut, LuaOptions, LuaResultTuple, MetaMethod as LuaMetaMethod,
+    MultiValue as LuaMultiValue, Nil as LuaNil, Number as LuaNumber, ObjectLike as LuaObjectLike,
+    RefThreadStats as LuaRefThreadStats, RegistryKey as LuaRegistryKey, Result as LuaResult,
+    SourceMap as LuaSourceMap, StackArgs as LuaStackArgs, StdLib as LuaStdLib, String as LuaString,
+    Table as LuaTable, TablePairs as LuaTablePairs, TablePairsOwned as LuaTablePairsOwned,
+    TableSequence as LuaTableSequence, TableShape as LuaTableShape, Thread as LuaThread,
     ThreadStatus as LuaThreadStatus, UserData as LuaUserData, UserDataFields as LuaUserDataFields,
     UserDataMetatable as LuaUserDataMetatable, UserDataMethods as LuaUserDataMethods,
     UserDataRef as LuaUserDataRef, UserDataRefMut as LuaUserDataRefMut,
@@ -20,7 +22,7 @@ pub use crate::{
 
 #[cfg(not(feature = "luau"))]
 #[doc(no_inline)]
-pub use crate::HookTriggers as LuaHookTriggers;
+pub use crate::{GlobalHook as LuaGlobalHook, HookTriggers as LuaHookTriggers};
 
 #[cfg(feature = "luau")]
 #[doc(no_inline)]
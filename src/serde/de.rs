@@ -68,6 +68,12 @@ pub struct Options {
     ///
     /// [`The Length Operator`]: https://www.lua.org/manual/5.4/manual.html#3.4.7
     pub detect_mixed_tables: bool,
+
+    /// If true, Luau buffers will be encoded as base64 strings.
+    /// Otherwise they are encoded as bytes (which is an array of integers in most formats).
+    ///
+    /// Default: **false**
+    pub buffers_as_base64: bool,
 }
 
 impl Default for Options {
@@ -85,6 +91,7 @@ impl Options {
             sort_keys: false,
             encode_empty_tables_as_array: false,
             detect_mixed_tables: false,
+            buffers_as_base64: false,
         }
     }
 
@@ -132,6 +139,15 @@ impl Options {
         self.detect_mixed_tables = enable;
         self
     }
+
+    /// Sets [`buffers_as_base64`] option.
+    ///
+    /// [`buffers_as_base64`]: #structfield.buffers_as_base64
+    #[must_use]
+    pub const fn buffers_as_base64(mut self, enabled: bool) -> Self {
+        self.buffers_as_base64 = enabled;
+        self
+    }
 }
 
 impl Deserializer {
@@ -199,6 +215,8 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
                 serde_userdata(ud, |value| value.deserialize_any(visitor))
             }
             #[cfg(feature = "luau")]
+            Value::Buffer(buf) if self.options.buffers_as_base64 => visitor.visit_string(buf.to_base64()),
+            #[cfg(feature = "luau")]
             Value::Buffer(buf) => {
                 let lua = buf.0.lua.lock();
                 visitor.visit_bytes(buf.as_slice(&lua))
@@ -5,7 +5,7 @@ use std::os::raw::c_void;
 use serde::de::DeserializeOwned;
 use serde::ser::Serialize;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::private::Sealed;
 use crate::state::util::get_next_spot;
 use crate::state::Lua;
@@ -120,6 +120,39 @@ pub trait LuaSerdeExt: Sealed {
     where
         T: Serialize + ?Sized;
 
+    /// Converts `T` into a new [`Table`].
+    ///
+    /// This is similar to [`LuaSerdeExt::to_value`], but guarantees a table result. Returns
+    /// [`Error::SerializeError`] if `T` does not serialize to a map, struct or sequence.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mluau::{Lua, Result, LuaSerdeExt};
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Config {
+    ///     name: String,
+    ///     workers: u32,
+    /// }
+    ///
+    /// fn main() -> Result<()> {
+    ///     let lua = Lua::new();
+    ///     let config = Config { name: "app".into(), workers: 4 };
+    ///     let t = lua.create_table_from_serialize(&config)?;
+    ///
+    ///     assert_eq!(t.get::<u32>("workers")?, 4);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`Error::SerializeError`]: crate::Error::SerializeError
+    fn create_table_from_serialize<T>(&self, t: &T) -> Result<Table>
+    where
+        T: Serialize + ?Sized;
+
     /// Deserializes a [`Value`] into any serde deserializable object.
     ///
     /// # Example
@@ -209,6 +242,19 @@ impl LuaSerdeExt for Lua {
         t.serialize(ser::Serializer::new_with_options(self, options))
     }
 
+    fn create_table_from_serialize<T>(&self, t: &T) -> Result<Table>
+    where
+        T: Serialize + ?Sized,
+    {
+        match self.to_value(t)? {
+            Value::Table(table) => Ok(table),
+            value => Err(Error::SerializeError(format!(
+                "expected a value serializing to a table, got {}",
+                value.type_name()
+            ))),
+        }
+    }
+
     fn from_value<T>(&self, value: Value) -> Result<T>
     where
         T: DeserializeOwned,
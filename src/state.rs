@@ -1,15 +1,16 @@
 use crate::chunk::{AsChunk, Chunk};
 use crate::debug::Debug;
 use crate::error::{Error, Result};
-use crate::function::Function;
+use crate::function::{Function, FunctionInfo};
 use crate::memory::MemoryState;
-use crate::multi::MultiValue;
+use crate::multi::{MultiValue, StackArgs};
 use crate::state::util::get_next_spot;
 use crate::stdlib::StdLib;
 use crate::string::String;
 use crate::table::Table;
 use crate::thread::Thread;
-use std::any::TypeId;
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
 use std::cell::{BorrowError, BorrowMutError, RefCell};
 #[cfg(all(not(feature = "lua51"), not(feature = "luajit")))]
 use std::ffi::CStr;
@@ -26,14 +27,17 @@ use crate::thread::ContinuationStatus;
 
 use crate::traits::{FromLua, FromLuaMulti, IntoLua, IntoLuaMulti};
 use crate::types::{
-    AppDataRef, AppDataRefMut, ArcReentrantMutexGuard, Integer, LuaType, MaybeSend, MaybeSync, Number, ReentrantMutex, ReentrantMutexGuard, RegistryKey, VmState, XRc, XWeak
+    AppDataRef, AppDataRefMut, ArcReentrantMutexGuard, Integer, LightRef, LuaType, MaybeSend, MaybeSync, Number, ReentrantMutex, ReentrantMutexGuard, RegistryKey, ThreadConfined, VmState, XRc, XWeak
 };
 use crate::userdata::{AnyUserData, UserData, UserDataProxy, UserDataRegistry, UserDataStorage};
 use crate::util::{assert_stack, check_stack, protect_lua_closure, push_string, rawset_field, StackGuard};
 use crate::value::{Nil, Value};
 
 #[cfg(not(feature = "luau"))]
-use crate::{debug::HookTriggers, types::HookKind};
+use crate::{
+    debug::{GlobalHook, HookTriggers},
+    types::HookKind,
+};
 
 #[cfg(feature = "luau")]
 use crate::types::ThreadData;
@@ -80,6 +84,33 @@ pub enum GCMode {
     Generational,
 }
 
+/// Lua implementation (backend) that mlua was compiled with.
+///
+/// Returned by [`Lua::backend`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LuaBackend {
+    Lua51,
+    Lua52,
+    Lua53,
+    Lua54,
+    LuaJit,
+    Luau,
+}
+
+/// Statistics of the auxiliary threads used to store references to Lua values.
+///
+/// Returned by [`Lua::ref_thread_stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RefThreadStats {
+    /// Number of auxiliary threads.
+    pub aux_threads: usize,
+    /// Total number of allocated slots in all auxiliary threads.
+    pub total_slots: usize,
+    /// Number of slots available for new references.
+    pub free_slots: usize,
+}
+
 /// Controls Lua interpreter behavior such as Rust panics handling.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
@@ -144,6 +175,12 @@ impl LuaOptions {
 impl Drop for Lua {
     fn drop(&mut self) {
         if self.collect_garbage {
+            if XRc::strong_count(&self.raw) == 1 {
+                let order = unsafe { mem::take(&mut (*self.lock().extra.get()).userdata_dtor_order) };
+                if !order.is_empty() {
+                    let _ = self.run_finalizers_in_order(&order);
+                }
+            }
             let _ = self.gc_collect();
         }
     }
@@ -161,7 +198,11 @@ impl Clone for Lua {
 
 impl fmt::Debug for Lua {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Lua({:p})", self.lock().state())
+        let lua = self.lock();
+        match unsafe { &(*lua.extra.get()).name } {
+            Some(name) => write!(f, "Lua({name:?}, {:p})", lua.state()),
+            None => write!(f, "Lua({:p})", lua.state()),
+        }
     }
 }
 
@@ -290,6 +331,43 @@ impl Lua {
         }
     }
 
+    /// Returns the Lua instance associated with a raw state, without initializing one.
+    ///
+    /// Unlike [`Lua::get_or_init_from_ptr`], this returns `None` if the state is not managed by
+    /// mlua, which makes it suitable for FFI bridges that receive a `lua_State` from C code and
+    /// need to check whether it belongs to a known instance.
+    ///
+    /// # Safety
+    /// `state` must be a valid Lua state and the `Lua` must outlive the chosen lifetime `'a`.
+    ///
+    /// With Luau (unless the `module` feature is enabled), the instance is looked up through
+    /// `(*lua_callbacks(state)).userdata` instead of the registry. This field must either be null
+    /// or point to the data set by mluau, so states whose callbacks userdata is used by other code
+    /// must not be passed here.
+    ///
+    /// # Example
+    /// ```
+    /// # use mluau::{Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// unsafe {
+    ///     // A state passed by `exec_raw` belongs to `lua`
+    ///     lua.exec_raw::<()>((), |state| {
+    ///         assert!(Lua::try_from_ptr(state).is_some());
+    ///     })?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub unsafe fn try_from_ptr<'a>(state: *mut ffi::lua_State) -> Option<&'a Lua> {
+        debug_assert!(!state.is_null(), "Lua state is null");
+        match ExtraData::get(state) {
+            extra if !extra.is_null() => Some((*extra).lua()),
+            _ => None,
+        }
+    }
+
     /// Calls provided function passing a raw lua state.
     ///
     /// The arguments will be pushed onto the stack before calling the function.
@@ -489,6 +567,13 @@ impl Lua {
     ///   environment.
     /// - Allow only `count` mode in `collectgarbage` function.
     ///
+    /// Sandbox mode can be toggled at any time, eg. to temporarily run trusted code. Disabling it
+    /// restores the original global environment and discards any globals set while sandboxed.
+    /// Threads created in sandbox mode keep the environment they were created with, and functions
+    /// loaded while sandboxed keep referencing the sandboxed environment.
+    ///
+    /// Use [`Lua::is_sandboxed`] to check the current mode.
+    ///
     /// # Examples
     ///
     /// ```
@@ -535,26 +620,89 @@ impl Lua {
         }
     }
 
+    /// Sets a name for this Lua instance.
+    ///
+    /// The name is only used for diagnostics: it is included in the `Debug` output of [`Lua`] and
+    /// can be retrieved with [`Lua::name`], for example to attribute errors to a specific VM when
+    /// running many of them.
+    pub fn set_name(&self, name: impl Into<StdString>) {
+        let lua = self.lock();
+        unsafe { (*lua.extra.get()).name = Some(name.into()) };
+    }
+
+    /// Returns the name of this Lua instance set by [`Lua::set_name`].
+    pub fn name(&self) -> Option<StdString> {
+        let lua = self.lock();
+        unsafe { (*lua.extra.get()).name.clone() }
+    }
+
+    /// Returns `true` if sandbox mode is currently enabled.
+    ///
+    /// See [`Lua::sandbox`] for details.
+    #[cfg(any(feature = "luau", doc))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "luau")))]
+    pub fn is_sandboxed(&self) -> bool {
+        unsafe { (*self.lock().extra.get()).sandboxed }
+    }
+
     /// Sets or replaces a global hook function that will periodically be called as Lua code
     /// executes.
     ///
     /// All new threads created (by mlua) after this call will use the global hook function.
     ///
+    /// Returns the previously installed global hook (if any), which can be called from the new
+    /// hook to compose them, or passed back to [`Lua::set_global_hook`] to restore it.
+    ///
     /// For more information see [`Lua::set_hook`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use mluau::{Lua, HookTriggers, Result, VmState};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// lua.set_global_hook(HookTriggers::EVERY_LINE, |_, _| Ok(VmState::Continue))?;
+    ///
+    /// // Temporarily replace the hook, then reinstall the previous one
+    /// let prev = lua.set_global_hook(HookTriggers::ON_CALLS, |_, _| Ok(VmState::Continue))?;
+    /// lua.load("local x = 1").exec()?;
+    /// if let Some(prev) = prev {
+    ///     lua.set_global_hook(prev.triggers(), move |lua, debug| prev.call(lua, debug))?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
     #[cfg(not(feature = "luau"))]
     #[cfg_attr(docsrs, doc(cfg(not(feature = "luau"))))]
-    pub fn set_global_hook<F>(&self, triggers: HookTriggers, callback: F) -> Result<()>
+    pub fn set_global_hook<F>(&self, triggers: HookTriggers, callback: F) -> Result<Option<GlobalHook>>
     where
         F: Fn(&Lua, &Debug) -> Result<VmState> + MaybeSend + 'static,
     {
         let lua = self.lock();
         unsafe {
-            (*lua.extra.get()).hook_triggers = triggers;
-            (*lua.extra.get()).hook_callback = Some(XRc::new(callback));
-            lua.set_thread_hook(lua.state(), HookKind::Global)
+            let extra = lua.extra.get();
+            let prev_triggers = mem::replace(&mut (*extra).hook_triggers, triggers);
+            let prev = (*extra).hook_callback.replace(XRc::new(callback));
+            lua.set_thread_hook(lua.state(), HookKind::Global)?;
+            Ok(prev.map(|callback| GlobalHook {
+                lua: lua.weak().clone(),
+                triggers: prev_triggers,
+                callback,
+            }))
         }
     }
 
+    /// Returns the triggers of the global hook set by [`Lua::set_global_hook`].
+    ///
+    /// Returns `None` if no global hook is installed.
+    #[cfg(not(feature = "luau"))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "luau"))))]
+    pub fn hook_triggers(&self) -> Option<HookTriggers> {
+        let lua = self.lock();
+        let extra = unsafe { &*lua.extra.get() };
+        extra.hook_callback.as_ref().map(|_| extra.hook_triggers)
+    }
+
     /// Sets a hook function that will periodically be called as Lua code executes.
     ///
     /// When exactly the hook function is called depends on the contents of the `triggers`
@@ -623,6 +771,58 @@ impl Lua {
         let lua = self.lock();
         unsafe {
             ffi::lua_sethook(lua.state(), None, 0, 0);
+            // Keep enforcing the call depth limit
+            if (*lua.extra.get()).max_call_depth.is_some() {
+                let res = lua.set_thread_hook(lua.state(), HookKind::Global);
+                mlua_expect!(res, "cannot set call depth hook");
+            }
+        }
+    }
+
+    /// Sets the maximum depth of the call stack for Lua code running in this instance.
+    ///
+    /// Once the number of active function calls (including Rust functions and the main chunk)
+    /// exceeds `depth`, an [`Error::StackError`] is raised instead of letting the recursion
+    /// exhaust the stack. This allows to safely run untrusted recursive code.
+    ///
+    /// In Luau the limit is checked by the VM interrupt, so it works together with
+    /// [`Lua::set_interrupt`]. In other Lua versions the limit is checked on every call by the
+    /// installed hook: call events are enabled in addition to the events requested by
+    /// [`Lua::set_hook`] or [`Lua::set_global_hook`], and the hook callbacks still receive only
+    /// the events they asked for. Threads (coroutines) have their own call stack and the limit
+    /// applies to each of them separately.
+    pub fn set_max_call_depth(&self, depth: u32) {
+        let lua = self.lock();
+        unsafe {
+            (*lua.extra.get()).max_call_depth = Some(depth);
+            #[cfg(feature = "luau")]
+            {
+                (*ffi::lua_callbacks(lua.main_state())).interrupt = Some(interrupt_proc);
+            }
+            #[cfg(not(feature = "luau"))]
+            {
+                let res = lua.set_thread_hook(lua.state(), HookKind::Refresh);
+                mlua_expect!(res, "cannot set call depth hook");
+            }
+        }
+    }
+
+    /// Removes the call stack depth limit previously set by [`Lua::set_max_call_depth`].
+    ///
+    /// In Lua 5.x the hook of the current thread is restored to the events requested by the user.
+    pub fn remove_max_call_depth(&self) {
+        let lua = self.lock();
+        unsafe {
+            (*lua.extra.get()).max_call_depth = None;
+            #[cfg(feature = "luau")]
+            if (*lua.extra.get()).interrupt_callback.is_none() {
+                (*ffi::lua_callbacks(lua.main_state())).interrupt = None;
+            }
+            #[cfg(not(feature = "luau"))]
+            {
+                let res = lua.set_thread_hook(lua.state(), HookKind::Refresh);
+                mlua_expect!(res, "cannot restore hook");
+            }
         }
     }
 
@@ -678,45 +878,6 @@ impl Lua {
     where
         F: Fn(&Lua) -> Result<VmState> + MaybeSend + 'static,
     {
-        unsafe extern "C-unwind" fn interrupt_proc(state: *mut ffi::lua_State, gc: c_int) {
-            if gc >= 0 {
-                // GC interrupts cannot survive Lua exceptions and hence abort if they throw
-                let extra = ExtraData::get(state);
-                if let Some(callback) = &(*extra).gc_interrupt_callback {
-                    use std::panic::{catch_unwind, AssertUnwindSafe};
-                    use std::process::abort;
-
-                    if XRc::strong_count(&callback) > 2 {
-                        return; // Don't allow recursion
-                    }
-                    (*extra).running_gc = true;
-                    match catch_unwind(AssertUnwindSafe(|| (callback)((*extra).lua(), gc))) {
-                        Ok(_) => {}
-                        Err(_) => abort(),
-                    };
-                    (*extra).running_gc = false;
-                }
-                return;
-            }
-            let result = callback_error_ext(state, ptr::null_mut(), false, move |extra, _| {
-                let interrupt_cb = (*extra).interrupt_callback.clone();
-                let interrupt_cb = mlua_expect!(interrupt_cb, "no interrupt callback set in interrupt_proc");
-                if XRc::strong_count(&interrupt_cb) > 2 {
-                    return Ok(VmState::Continue); // Don't allow recursion
-                }
-                interrupt_cb((*extra).lua())
-            });
-            match result {
-                VmState::Continue => {}
-                VmState::Yield => {
-                    // We can yield only at yieldable points, otherwise ignore and continue
-                    if ffi::lua_isyieldable(state) != 0 {
-                        ffi::lua_yield(state, 0);
-                    }
-                }
-            }
-        }
-
         // Set interrupt callback
         let lua = self.lock_gc_safe();
         unsafe {
@@ -734,7 +895,9 @@ impl Lua {
         let lua = self.lock_gc_safe();
         unsafe {
             (*lua.extra.get()).interrupt_callback = None;
-            (*ffi::lua_callbacks(lua.main_state())).interrupt = None;
+            if (*lua.extra.get()).max_call_depth.is_none() {
+                (*ffi::lua_callbacks(lua.main_state())).interrupt = None;
+            }
         }
     }
 
@@ -768,6 +931,13 @@ impl Lua {
     }
 
     /// Sets a thread creation callback that will be called when a thread is created.
+    ///
+    /// The callback is called for every new thread, including coroutines created from Lua code
+    /// (eg. using `coroutine.create`). An error returned from the callback is raised in the
+    /// parent thread.
+    ///
+    /// Together with [`Lua::set_thread_collection_callback`] this allows to track the lifecycle
+    /// of threads (eg. for pooling or accounting).
     #[cfg(any(feature = "luau", doc))]
     #[cfg_attr(docsrs, doc(cfg(feature = "luau")))]
     pub fn set_thread_creation_callback<F>(&self, callback: F)
@@ -882,6 +1052,77 @@ impl Lua {
         }
     }
 
+    /// Sets a callback to resolve native (C) modules required from Lua through Rust.
+    ///
+    /// The callback is called by `require` with the module name, before the standard C module
+    /// searchers. It can return a loader [`Function`] for the module, or `None` to let other
+    /// searchers handle the request.
+    ///
+    /// This allows to allow specific native modules in safe mode, where loading C modules
+    /// is disabled otherwise. Calling this function again replaces the previous callback.
+    ///
+    /// Requires the `package` library to be loaded.
+    #[cfg(not(feature = "luau"))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "luau"))))]
+    pub fn set_c_module_loader<F>(&self, callback: F) -> Result<()>
+    where
+        F: Fn(&Lua, &str) -> Result<Option<Function>> + MaybeSend + 'static,
+    {
+        const SEARCHER_KEY: &str = "__mlua_c_module_searcher";
+
+        let package: Table = self.globals().get("package")?;
+        #[cfg(any(feature = "lua54", feature = "lua53", feature = "lua52"))]
+        let searchers: Table = package.get("searchers")?;
+        #[cfg(any(feature = "lua51", feature = "luajit"))]
+        let searchers: Table = package.get("loaders")?;
+
+        let searcher = self.create_function(move |lua, name: StdString| match callback(lua, &name)? {
+            Some(loader) => Ok(Value::Function(loader)),
+            None => format!("\n\tno native module '{name}'").into_lua(lua),
+        })?;
+
+        // Replace the previously set searcher (if any) or insert a new one before C searchers
+        let prev_searcher = self.named_registry_value::<Option<Function>>(SEARCHER_KEY)?;
+        let mut position = None;
+        if let Some(prev_searcher) = prev_searcher {
+            for (i, s) in searchers.sequence_values::<Value>().enumerate() {
+                if s?.as_function() == Some(&prev_searcher) {
+                    position = Some(i + 1);
+                    break;
+                }
+            }
+        }
+        match position {
+            Some(i) => searchers.raw_set(i, &searcher)?,
+            None => searchers.raw_insert(3, &searcher)?,
+        }
+        self.set_named_registry_value(SEARCHER_KEY, searcher)
+    }
+
+    /// Sets a handler that is called when a Rust callback panics.
+    ///
+    /// The handler receives the panic payload and is invoked at the point where the panic is
+    /// caught, before it is propagated through Lua. This allows to log the panic with full
+    /// context (eg. using [`Lua::traceback`]) before the stack is unwound.
+    ///
+    /// The panic is propagated as usual after the handler returns. Panics inside the handler
+    /// are ignored.
+    pub fn set_panic_handler<F>(&self, handler: F)
+    where
+        F: Fn(&Lua, &(dyn Any + Send)) + MaybeSend + 'static,
+    {
+        let lua = self.lock();
+        unsafe { (*lua.extra.get()).panic_handler = Some(XRc::new(handler)) };
+    }
+
+    /// Removes the panic handler previously set by [`Lua::set_panic_handler`].
+    ///
+    /// This function has no effect if a panic handler was not previously set.
+    pub fn remove_panic_handler(&self) {
+        let lua = self.lock();
+        unsafe { (*lua.extra.get()).panic_handler = None };
+    }
+
     /// Sets the warning function to be used by Lua to emit warnings.
     #[cfg(feature = "lua54")]
     #[cfg_attr(docsrs, doc(cfg(feature = "lua54")))]
@@ -968,6 +1209,80 @@ impl Lua {
         }
     }
 
+    /// Returns information about the function running at a given level of the call stack.
+    ///
+    /// Level `0` is the current running function (eg. the Rust callback calling this method),
+    /// whereas level `n+1` is the function that has called level `n`. Unlike [`Function::info`],
+    /// the function name is resolved from the calling frame, so this can be used to reconstruct
+    /// the chain of calls (eg. in a crash reporter).
+    ///
+    /// Returns `None` if the level is greater than the stack depth.
+    pub fn frame_info(&self, level: usize) -> Option<FunctionInfo> {
+        self.inspect_stack(level, |debug| {
+            let names = debug.names();
+            let source = debug.source();
+            FunctionInfo {
+                name: names.name.map(Cow::into_owned),
+                name_what: names.name_what,
+                what: source.what,
+                source: source.source.map(Cow::into_owned),
+                short_src: source.short_src.map(Cow::into_owned),
+                line_defined: source.line_defined,
+                last_line_defined: source.last_line_defined,
+            }
+        })
+    }
+
+    /// Returns the source location of the innermost Lua function on the call stack.
+    ///
+    /// The location is returned as a pair of the chunk short source name and the current line.
+    /// Rust (and C) functions are skipped.
+    ///
+    /// This can be used from within callbacks, for example in an interrupt or hook callback to
+    /// find out where the script is currently executing.
+    pub fn current_location(&self) -> Option<(StdString, usize)> {
+        let mut level = 0;
+        loop {
+            let location = self.inspect_stack(level, |debug| {
+                let line = debug.current_line()?;
+                let short_src = debug.source().short_src?.into_owned();
+                Some((short_src, line))
+            })?;
+            if location.is_some() {
+                return location;
+            }
+            level += 1;
+        }
+    }
+
+    /// Returns the last line number published by a running hook or interrupt.
+    ///
+    /// When a line hook (see [`Lua::set_hook`]) is installed, the currently executing line is
+    /// stored in an atomic on every invocation. On Luau, interrupts (see [`Lua::set_interrupt`])
+    /// publish the line as well once enabled with [`Lua::track_interrupt_line`], as looking it up
+    /// has a cost. This method reads it without locking the VM, so a watchdog thread can find out
+    /// where a running VM is spending its time without pausing it.
+    ///
+    /// Returns `None` if no line has been published yet.
+    pub fn current_line_atomic(&self) -> Option<u32> {
+        // Read through a raw pointer: the lock may be held by the thread running Lua code.
+        // The counter has its own allocation and is never replaced, so this doesn't race.
+        let current_line = unsafe { &(*self.raw.data_ptr()).current_line };
+        let line = current_line.load(std::sync::atomic::Ordering::Relaxed);
+        (line != 0).then_some(line)
+    }
+
+    /// Enables or disables publishing of the current line from interrupts.
+    ///
+    /// When enabled, every interrupt looks up the currently executing line and makes it available
+    /// through [`Lua::current_line_atomic`]. Disabled by default.
+    #[cfg(any(feature = "luau", doc))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "luau")))]
+    pub fn track_interrupt_line(&self, enabled: bool) {
+        let lua = self.lock();
+        unsafe { (*lua.extra.get()).track_interrupt_line = enabled };
+    }
+
     /// Creates a traceback of the call stack at the given level.
     ///
     /// The `msg` parameter, if provided, is added at the beginning of the traceback.
@@ -988,23 +1303,96 @@ impl Lua {
         }
     }
 
+    /// Returns the Lua implementation this instance is running on.
+    ///
+    /// Useful for modules loaded into a host binary that need to adapt to the backend at runtime.
+    pub fn backend(&self) -> LuaBackend {
+        if cfg!(feature = "luau") {
+            LuaBackend::Luau
+        } else if cfg!(feature = "luajit") {
+            LuaBackend::LuaJit
+        } else if cfg!(feature = "lua54") {
+            LuaBackend::Lua54
+        } else if cfg!(feature = "lua53") {
+            LuaBackend::Lua53
+        } else if cfg!(feature = "lua52") {
+            LuaBackend::Lua52
+        } else {
+            LuaBackend::Lua51
+        }
+    }
+
+    /// Returns a human readable version of the Lua implementation, eg. `"Lua 5.4"`.
+    ///
+    /// For Luau the version number is included when known (eg. `"Luau 0.650"`).
+    pub fn version_string(&self) -> StdString {
+        #[cfg(feature = "luau")]
+        if let Some(version) = ffi::luau_version() {
+            return format!("Luau {version}");
+        }
+        let version = match self.backend() {
+            LuaBackend::Lua51 => "Lua 5.1",
+            LuaBackend::Lua52 => "Lua 5.2",
+            LuaBackend::Lua53 => "Lua 5.3",
+            LuaBackend::Lua54 => "Lua 5.4",
+            LuaBackend::LuaJit => "LuaJIT",
+            LuaBackend::Luau => "Luau",
+        };
+        version.to_string()
+    }
+
+    /// Returns statistics of the auxiliary threads that hold references to Lua values.
+    ///
+    /// Every live handle (such as [`Table`] or [`Function`]) occupies a slot in these threads.
+    /// Steadily decreasing free slots (or increasing total slots) usually indicates that handles
+    /// are leaked on the Rust side.
+    pub fn ref_thread_stats(&self) -> RefThreadStats {
+        let lua = self.lock();
+        let extra = unsafe { &*lua.extra.get() };
+        let mut stats = RefThreadStats {
+            aux_threads: extra.ref_thread.len(),
+            ..Default::default()
+        };
+        for ref_thread in &extra.ref_thread {
+            stats.total_slots += ref_thread.stack_size as usize;
+            stats.free_slots +=
+                (ref_thread.stack_size - ref_thread.stack_top) as usize + ref_thread.free.len();
+        }
+        stats
+    }
+
     /// Returns the amount of memory (in bytes) currently used inside this Lua state.
+    ///
+    /// The value is taken from the mlua allocator accounting when available, otherwise
+    /// (eg. in module mode) it falls back to [`Lua::gc_count_bytes`].
     pub fn used_memory(&self) -> usize {
         let lua = self.lock();
         let state = lua.main_state();
         unsafe {
             match MemoryState::get(state) {
                 mem_state if !mem_state.is_null() => (*mem_state).used_memory(),
-                _ => {
-                    // Get data from the Lua GC
-                    let used_kbytes = ffi::lua_gc(state, ffi::LUA_GCCOUNT, 0);
-                    let used_kbytes_rem = ffi::lua_gc(state, ffi::LUA_GCCOUNTB, 0);
-                    (used_kbytes as usize) * 1024 + (used_kbytes_rem as usize)
-                }
+                _ => self.gc_count_bytes(),
             }
         }
     }
 
+    /// Returns the amount of memory (in bytes) in use as reported by the Lua GC
+    /// (`LUA_GCCOUNT`/`LUA_GCCOUNTB`).
+    ///
+    /// This can differ from [`Lua::used_memory`], which uses the allocator's own accounting when
+    /// the Lua state is created by mlua. For example, the allocator counts the memory of all
+    /// allocations (including ones not yet accounted by the GC), while the GC count may lag behind
+    /// during an incremental collection cycle.
+    pub fn gc_count_bytes(&self) -> usize {
+        let lua = self.lock();
+        let state = lua.main_state();
+        unsafe {
+            let used_kbytes = ffi::lua_gc(state, ffi::LUA_GCCOUNT, 0);
+            let used_kbytes_rem = ffi::lua_gc(state, ffi::LUA_GCCOUNTB, 0);
+            (used_kbytes as usize) * 1024 + (used_kbytes_rem as usize)
+        }
+    }
+
     /// Sets a memory limit (in bytes) on this Lua state.
     ///
     /// Once an allocation occurs that would pass this memory limit, a `Error::MemoryError` is
@@ -1035,6 +1423,22 @@ impl Lua {
         }
     }
 
+    /// Returns the total number of allocations made by this Lua state since it was created.
+    ///
+    /// Only new allocations are counted (reallocations and frees are not). Together with a fixed
+    /// input, this allows asserting that an operation allocates an exact number of times.
+    ///
+    /// Does not work in module mode where Lua state is managed externally.
+    pub fn allocation_count(&self) -> Result<u64> {
+        let lua = self.lock();
+        unsafe {
+            match MemoryState::get(lua.state()) {
+                mem_state if !mem_state.is_null() => Ok((*mem_state).allocation_count()),
+                _ => Err(Error::MemoryControlNotAvailable),
+            }
+        }
+    }
+
     /// Returns `true` if the garbage collector is currently running automatically.
     #[cfg(any(feature = "lua54", feature = "lua53", feature = "lua52", feature = "luau"))]
     pub fn gc_is_running(&self) -> bool {
@@ -1042,16 +1446,46 @@ impl Lua {
         unsafe { ffi::lua_gc(lua.main_state(), ffi::LUA_GCISRUNNING, 0) != 0 }
     }
 
+    /// Returns `true` if the garbage collector is enabled (not stopped).
+    ///
+    /// Unlike [`Lua::gc_is_running`], this method is available on all backends.
+    /// Lua 5.1 and LuaJIT do not expose the GC state, so only changes made through
+    /// [`Lua::gc_stop`] and [`Lua::gc_restart`] are tracked there
+    /// (calling `collectgarbage("stop")` from Lua is not reflected).
+    pub fn gc_is_enabled(&self) -> bool {
+        let lua = self.lock();
+        #[cfg(any(feature = "lua54", feature = "lua53", feature = "lua52", feature = "luau"))]
+        unsafe {
+            ffi::lua_gc(lua.main_state(), ffi::LUA_GCISRUNNING, 0) != 0
+        }
+        #[cfg(any(feature = "lua51", feature = "luajit"))]
+        unsafe {
+            !(*lua.extra.get()).gc_stopped
+        }
+    }
+
     /// Stop the Lua GC from running
     pub fn gc_stop(&self) {
         let lua = self.lock();
-        unsafe { ffi::lua_gc(lua.main_state(), ffi::LUA_GCSTOP, 0) };
+        unsafe {
+            ffi::lua_gc(lua.main_state(), ffi::LUA_GCSTOP, 0);
+            #[cfg(any(feature = "lua51", feature = "luajit"))]
+            {
+                (*lua.extra.get()).gc_stopped = true;
+            }
+        }
     }
 
     /// Restarts the Lua GC if it is not running
     pub fn gc_restart(&self) {
         let lua = self.lock();
-        unsafe { ffi::lua_gc(lua.main_state(), ffi::LUA_GCRESTART, 0) };
+        unsafe {
+            ffi::lua_gc(lua.main_state(), ffi::LUA_GCRESTART, 0);
+            #[cfg(any(feature = "lua51", feature = "luajit"))]
+            {
+                (*lua.extra.get()).gc_stopped = false;
+            }
+        }
     }
 
     /// Perform a full garbage-collection cycle.
@@ -1067,6 +1501,32 @@ impl Lua {
         }
     }
 
+    /// Performs a full garbage-collection cycle and returns the number of bytes reclaimed.
+    ///
+    /// The amount is measured as the difference in [`Lua::used_memory`] before and after the
+    /// collection, while holding the Lua lock for the whole operation.
+    pub fn gc_collect_measured(&self) -> Result<usize> {
+        let _lua = self.lock();
+        let before = self.used_memory();
+        self.gc_collect()?;
+        Ok(before.saturating_sub(self.used_memory()))
+    }
+
+    /// Performs a full garbage-collection cycle if it is currently safe to do so.
+    ///
+    /// Unlike [`Lua::gc_collect`], this method can be called from any callback. It returns an error
+    /// instead of collecting when the garbage collector itself is running, for example inside a
+    /// GC interrupt callback or while a userdata destructor is executing (Luau).
+    pub fn try_gc_collect(&self) -> Result<()> {
+        #[cfg(feature = "luau")]
+        if unsafe { (*self.raw.lock().extra.get()).running_gc } {
+            return Err(Error::runtime(
+                "cannot collect garbage while the garbage collector is running",
+            ));
+        }
+        self.gc_collect()
+    }
+
     /// Steps the garbage collector one indivisible step.
     ///
     /// Returns `true` if this has finished a collection cycle.
@@ -1089,6 +1549,28 @@ impl Lua {
         }
     }
 
+    /// Runs the garbage collector incrementally until a collection cycle is finished.
+    ///
+    /// The collector is stepped with [`Lua::gc_step_kbytes`] using `step_kbytes`, and `on_step` is
+    /// called between the steps. If `on_step` returns `false` (eg. the frame time budget is
+    /// exhausted), the collection is suspended and can be continued later.
+    ///
+    /// Returns `true` if a collection cycle has been finished.
+    pub fn gc_collect_incremental(
+        &self,
+        step_kbytes: c_int,
+        mut on_step: impl FnMut() -> bool,
+    ) -> Result<bool> {
+        loop {
+            if self.gc_step_kbytes(step_kbytes)? {
+                return Ok(true);
+            }
+            if !on_step() {
+                return Ok(false);
+            }
+        }
+    }
+
     /// Sets the `pause` value of the collector.
     ///
     /// Returns the previous value of `pause`. More information can be found in the Lua
@@ -1200,6 +1682,38 @@ impl Lua {
         unsafe { (*lua.extra.get()).compiler = Some(compiler) };
     }
 
+    /// Returns the default Luau compiler, if one was set using [`Lua::set_compiler`].
+    #[cfg(any(feature = "luau", doc))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "luau")))]
+    pub fn compiler(&self) -> Option<Compiler> {
+        let lua = self.lock();
+        unsafe { (*lua.extra.get()).compiler.clone() }
+    }
+
+    /// Compiles Luau source code into bytecode without loading it.
+    ///
+    /// The default compiler set using [`Lua::set_compiler`] is used, if any. The returned bytecode
+    /// can be stored and later loaded by any Lua instance using [`Lua::load`].
+    #[cfg(any(feature = "luau", doc))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "luau")))]
+    pub fn compile(&self, source: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+        self.compiler().unwrap_or_default().compile(source)
+    }
+
+    /// Returns the module paths required by the given Luau source code, without executing it.
+    ///
+    /// The source is first compiled to check that it's valid, then calls in the form of
+    /// `require("path")`, `require "path"` or `require [[path]]` are collected in the order of
+    /// appearance. Calls with non-literal (computed) arguments are ignored.
+    ///
+    /// This is useful for build tools computing dependency graphs of modules.
+    #[cfg(any(feature = "luau", doc))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "luau")))]
+    pub fn analyze_requires(&self, source: &str) -> Result<Vec<StdString>> {
+        self.compile(source)?;
+        Ok(crate::luau::find_requires(source.as_bytes()))
+    }
+
     /// Toggles JIT compilation mode for new chunks of code.
     ///
     /// By default JIT is enabled. Changing this option does not have any effect on
@@ -1211,6 +1725,16 @@ impl Lua {
         unsafe { (*lua.extra.get()).enable_jit = enable };
     }
 
+    /// Returns `true` if new chunks of code are JIT compiled.
+    ///
+    /// See [`Lua::enable_jit`] for details.
+    #[cfg(any(feature = "luau-jit", doc))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "luau-jit")))]
+    pub fn is_jit_enabled(&self) -> bool {
+        let lua = self.lock();
+        unsafe { (*lua.extra.get()).enable_jit }
+    }
+
     /// Sets Luau feature flag (global setting).
     ///
     /// See https://github.com/luau-lang/luau/blob/master/CONTRIBUTING.md#feature-flags for details.
@@ -1222,19 +1746,194 @@ impl Lua {
                 return Ok(());
             }
         }
-        Err(())
-    }
-
-    /// Returns Lua source code as a `Chunk` builder type.
-    ///
-    /// In order to actually compile or run the resulting code, you must call [`Chunk::exec`] or
-    /// similar on the returned builder. Code is not even parsed until one of these methods is
-    /// called.
-    ///
-    /// [`Chunk::exec`]: crate::Chunk::exec
-    #[track_caller]
-    pub fn load<'a>(&self, chunk: impl AsChunk + 'a) -> Chunk<'a> {
-        self.load_with_location(chunk, Location::caller())
+        Err(())
+    }
+
+    /// Returns Lua source code as a `Chunk` builder type.
+    ///
+    /// In order to actually compile or run the resulting code, you must call [`Chunk::exec`] or
+    /// similar on the returned builder. Code is not even parsed until one of these methods is
+    /// called.
+    ///
+    /// [`Chunk::exec`]: crate::Chunk::exec
+    #[track_caller]
+    pub fn load<'a>(&self, chunk: impl AsChunk + 'a) -> Chunk<'a> {
+        self.load_with_location(chunk, Location::caller())
+    }
+
+    /// Returns owned Lua source code as a `Chunk` builder type.
+    ///
+    /// This is the same as [`Lua::load`], but takes ownership of the source buffer instead of
+    /// copying it, which avoids an extra allocation for generated `String` or `Vec<u8>` sources.
+    #[track_caller]
+    pub fn load_owned(&self, source: impl Into<Vec<u8>>) -> Chunk<'static> {
+        let mut chunk = self.load_with_location(&b""[..], Location::caller());
+        chunk.source = Ok(Cow::Owned(source.into()));
+        chunk
+    }
+
+    /// Checks that the given source code is syntactically valid without running it.
+    ///
+    /// On Luau the source is compiled (using the compiler set by [`Lua::set_compiler`], if any)
+    /// and the bytecode is discarded. On other backends the source is loaded as a text chunk and
+    /// the resulting function is dropped. Either way no code is executed.
+    ///
+    /// Returns [`Error::SyntaxError`] if the source fails to parse. The error message contains the
+    /// line number of the problem.
+    pub fn check_syntax(&self, source: &str) -> Result<()> {
+        #[cfg(feature = "luau")]
+        self.compile(source)?;
+        #[cfg(not(feature = "luau"))]
+        (self.load(source))
+            .set_name("=source")
+            .set_mode(crate::chunk::ChunkMode::Text)
+            .into_function()?;
+        Ok(())
+    }
+
+    /// Runs the chunk in a new thread (coroutine) and returns its results.
+    ///
+    /// The chunk does not share the stack with the calling thread, so it cannot leave any values
+    /// (like to-be-closed variables) behind. If the chunk yields, it's resumed without arguments
+    /// until it finishes. The thread is discarded afterwards (closed on Lua 5.4 and Luau), even if
+    /// an error occurs.
+    pub fn exec_isolated<R: FromLuaMulti>(&self, chunk: Chunk) -> Result<R> {
+        let thread = self.create_thread(chunk.into_function()?)?;
+        let mut result = thread.resume::<MultiValue>(());
+        while result.is_ok() && thread.status() == crate::ThreadStatus::Resumable {
+            result = thread.resume::<MultiValue>(());
+        }
+        #[cfg(any(feature = "lua54", feature = "luau"))]
+        let closed = thread.close();
+        let values = result?;
+        #[cfg(any(feature = "lua54", feature = "luau"))]
+        closed?;
+        R::from_lua_multi(values, self)
+    }
+
+    /// Sets the default environment for chunks loaded by this Lua instance.
+    ///
+    /// The environment is used by [`Lua::load`] when the chunk has no environment of its own (see
+    /// [`Chunk::set_environment`]). This allows to load all scripts into a restricted environment
+    /// without having to remember setting it for every chunk.
+    ///
+    /// Passing `None` restores the default behavior of using the globals table.
+    pub fn set_default_chunk_env(&self, env: Option<Table>) {
+        let lua = self.lock();
+        unsafe { (*lua.extra.get()).default_chunk_env = env };
+    }
+
+    /// Returns the default environment for loaded chunks set by [`Lua::set_default_chunk_env`].
+    pub fn default_chunk_env(&self) -> Option<Table> {
+        let lua = self.lock();
+        unsafe { (*lua.extra.get()).default_chunk_env.clone() }
+    }
+
+    /// Replaces the global `print` function with one that forwards output to `f`.
+    ///
+    /// The arguments are converted to strings using the `tostring` semantics (invoking the
+    /// `__tostring` metamethod when present) and joined with tabs, exactly as the standard `print`
+    /// does. The resulting line (without a trailing newline) is passed to the callback.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mluau::{Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// lua.set_print(|_, line| {
+    ///     println!("[script] {line}");
+    ///     Ok(())
+    /// })?;
+    /// lua.load("print('hello', 1, true)").exec()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_print<F>(&self, f: F) -> Result<()>
+    where
+        F: Fn(&Lua, &str) -> Result<()> + MaybeSend + 'static,
+    {
+        let print = self.create_function(move |lua, args: MultiValue| {
+            let mut line = StdString::new();
+            for (i, arg) in args.iter().enumerate() {
+                if i > 0 {
+                    line.push('\t');
+                }
+                match arg {
+                    // Non-UTF8 strings must not fail printing
+                    Value::String(s) => line.push_str(&s.to_string_lossy()),
+                    _ => line.push_str(&arg.to_string()?),
+                }
+            }
+            f(lua, &line)
+        })?;
+        self.globals().raw_set("print", print)
+    }
+
+    /// Copies a value from this Lua instance into the `target` instance.
+    ///
+    /// Plain data is deep-copied: `nil`, booleans, numbers, strings, vectors, buffers and tables
+    /// (preserving shared references and cycles). Table metatables are not copied.
+    ///
+    /// Values bound to the source VM (functions, threads and userdata) cannot be transferred and
+    /// result in an error, as do tables nested deeper than 200 levels.
+    ///
+    /// This is useful for passing data between independent VMs without a serialization roundtrip.
+    pub fn transfer_value(&self, value: &Value, target: &Lua) -> Result<Value> {
+        use std::collections::HashMap;
+
+        const MAX_DEPTH: usize = 200;
+
+        // Copies a single value, queueing the contents of new tables to be copied later
+        fn transfer(
+            value: &Value,
+            depth: usize,
+            target: &Lua,
+            tables: &mut HashMap<*const std::ffi::c_void, Table>,
+            pending: &mut Vec<(Table, Table, usize)>,
+        ) -> Result<Value> {
+            Ok(match value {
+                Value::String(s) => Value::String(target.create_string(s.as_bytes())?),
+                #[cfg(feature = "luau")]
+                Value::Buffer(buf) => Value::Buffer(target.create_buffer(buf.to_vec())?),
+                Value::Table(t) => {
+                    if let Some(copy) = tables.get(&t.to_pointer()) {
+                        return Ok(Value::Table(copy.clone()));
+                    }
+                    if depth > MAX_DEPTH {
+                        let msg = format!("cannot transfer tables nested deeper than {MAX_DEPTH} levels");
+                        return Err(Error::runtime(msg));
+                    }
+                    let copy = target.create_table()?;
+                    tables.insert(t.to_pointer(), copy.clone());
+                    pending.push((t.clone(), copy.clone(), depth));
+                    Value::Table(copy)
+                }
+                Value::Function(_) | Value::Thread(_) | Value::UserData(_) | Value::Other(_) => {
+                    let msg = format!("cannot transfer {} between Lua instances", value.type_name());
+                    return Err(Error::runtime(msg));
+                }
+                value => value.clone(),
+            })
+        }
+
+        let mut tables = HashMap::new();
+        let mut pending = Vec::new();
+        let result = transfer(value, 0, target, &mut tables, &mut pending)?;
+        while let Some((table, copy, depth)) = pending.pop() {
+            // Collect the pairs first, so the source is not locked while writing to the target
+            let mut pairs = Vec::new();
+            table.for_each::<Value, Value>(|k, v| {
+                pairs.push((k, v));
+                Ok(())
+            })?;
+            for (k, v) in pairs {
+                let k = transfer(&k, depth + 1, target, &mut tables, &mut pending)?;
+                let v = transfer(&v, depth + 1, target, &mut tables, &mut pending)?;
+                copy.raw_set(k, v)?;
+            }
+        }
+        Ok(result)
     }
 
     pub(crate) fn load_with_location<'a>(
@@ -1247,11 +1946,17 @@ impl Lua {
             name: chunk
                 .name()
                 .unwrap_or_else(|| format!("@{}:{}", location.file(), location.line())),
-            env: chunk.environment(self),
+            env: chunk
+                .environment(self)
+                .map(|env| env.or_else(|| self.default_chunk_env())),
+            name_error: None,
             mode: chunk.mode(),
             source: chunk.source(),
             #[cfg(feature = "luau")]
             compiler: unsafe { (*self.lock().extra.get()).compiler.clone() },
+            #[cfg(feature = "luau")]
+            retain_bytecode: false,
+            source_map: None,
         }
     }
 
@@ -1264,6 +1969,32 @@ impl Lua {
         unsafe { self.lock().create_string(s.as_ref()) }
     }
 
+    /// Creates a Lua error value from the given Rust error.
+    ///
+    /// The returned [`Value::Error`] is passed to Lua as the same error object that is raised when
+    /// a Rust callback returns an error. It can be stored in tables and raised later with the
+    /// Lua `error` function.
+    #[inline]
+    pub fn create_error(&self, err: impl Into<Error>) -> Result<Value> {
+        Ok(Value::Error(Box::new(err.into())))
+    }
+
+    /// Turns a captured Lua error value back into an [`Error`] that can be returned from a callback.
+    ///
+    /// If `err` is a [`Value::Error`] (e.g. an error caught by `pcall` and passed to Rust), the
+    /// original error is returned as is, including its [`Error::CallbackError`] traceback.
+    /// When returned from a callback, an [`Error::CallbackError`] is propagated unchanged, without
+    /// being wrapped again or getting a new traceback. Any other value is converted to
+    /// [`Error::RuntimeError`] using its string representation.
+    pub fn raise(&self, err: Value) -> Error {
+        let err = Error::from_lua(err, self).unwrap_or_else(|err| err);
+        if let Error::CallbackError { cause, .. } = &err {
+            let lua = self.lock();
+            unsafe { (*lua.extra.get()).reraised_error = Some(std::sync::Arc::downgrade(cause)) };
+        }
+        err
+    }
+
     /// Creates and returns a Luau [buffer] object from a byte slice of data.
     ///
     /// [buffer]: https://luau.org/library#buffer-library
@@ -1290,6 +2021,31 @@ impl Lua {
         unsafe { Ok(self.lock().create_buffer_with_capacity(size)?.1) }
     }
 
+    /// Exposes a mutable slice to Lua as a Luau [buffer] for the duration of `f`.
+    ///
+    /// The slice contents are copied to a new buffer which is passed to `f`. Once `f` returns
+    /// successfully, the buffer contents are copied back to the slice, so scripts can mutate the
+    /// data in place (eg. process an audio frame).
+    ///
+    /// Luau buffers cannot be detached, so after `f` returns the buffer is filled with zeros.
+    /// Any reference to it kept by Lua code does not see the slice data anymore and writes to it
+    /// are not reflected in the slice.
+    ///
+    /// [buffer]: https://luau.org/library#buffer-library
+    #[cfg(any(feature = "luau", doc))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "luau")))]
+    pub fn with_mut_slice<T, R>(&self, slice: &mut [T], f: impl FnOnce(Buffer) -> Result<R>) -> Result<R>
+    where
+        T: crate::buffer::BufferElement,
+    {
+        let size = mem::size_of_val(slice);
+        let bytes = unsafe { std::slice::from_raw_parts(slice.as_ptr() as *const u8, size) };
+        let buffer = self.create_buffer(bytes)?;
+        let result = f(buffer.clone());
+        buffer.drain_into(result.is_ok().then_some(slice));
+        result
+    }
+
     /// Creates and returns a new empty table.
     #[inline]
     pub fn create_table(&self) -> Result<Table> {
@@ -1323,6 +2079,48 @@ impl Lua {
         unsafe { self.lock().create_sequence_from(iter) }
     }
 
+    /// Creates a table from a fallible iterator of values, using `1..` as the keys.
+    ///
+    /// Unlike [`Lua::create_sequence_from`], the iterator is consumed in batches and the Lua lock
+    /// is released between them, so generating a very large table from a streaming source does not
+    /// block other threads for the whole duration. The iterator's `size_hint` is not used.
+    ///
+    /// The first error returned by the iterator is propagated and the partially filled table is
+    /// discarded.
+    pub fn try_create_sequence_from<T>(&self, iter: impl IntoIterator<Item = Result<T>>) -> Result<Table>
+    where
+        T: IntoLua,
+    {
+        const BATCH_SIZE: usize = 1024;
+
+        let table = self.create_table()?;
+        let mut iter = iter.into_iter().fuse();
+        let mut len: Integer = 0;
+        loop {
+            let lua = self.lock();
+            let state = lua.state();
+            unsafe {
+                let _sg = StackGuard::new(state);
+                check_stack(state, 4)?;
+
+                lua.push_ref_at(&table.0, state);
+                let protect = !lua.unlikely_memory_error();
+                for _ in 0..BATCH_SIZE {
+                    let Some(value) = iter.next() else {
+                        return Ok(table);
+                    };
+                    lua.push_at(state, value?)?;
+                    len += 1;
+                    if protect {
+                        protect_lua!(state, 2, 1, |state| ffi::lua_rawseti(state, -2, len))?;
+                    } else {
+                        ffi::lua_rawseti(state, -2, len);
+                    }
+                }
+            }
+        }
+    }
+
     /// Wraps a Rust function or closure, creating a callable Lua function handle to it.
     ///
     /// The function's return value is always a `Result`: If the function returns `Err`, the error
@@ -1378,6 +2176,38 @@ impl Lua {
         }))
     }
 
+    /// Wraps a Rust function or closure, leaving its arguments on the Lua stack.
+    ///
+    /// Unlike [`Lua::create_function`], the arguments are not converted upfront. Instead, the
+    /// function receives [`StackArgs`] which converts each argument only when requested. This
+    /// is useful for variadic functions that often ignore most of their arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mluau::{Lua, Result, StackArgs};
+    /// # fn main() -> Result<()> {
+    /// # let lua = Lua::new();
+    /// // Returns the first argument and the number of arguments
+    /// let first = lua.create_raw_function(|_, args: StackArgs| {
+    ///     Ok((args.get::<Option<String>>(0)?, args.len()))
+    /// })?;
+    /// assert_eq!(first.call::<(String, usize)>(("a", 1, 2))?, ("a".to_string(), 3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_raw_function<F, R>(&self, func: F) -> Result<Function>
+    where
+        F: Fn(&Lua, StackArgs) -> Result<R> + MaybeSend + 'static,
+        R: IntoLuaMulti,
+    {
+        (self.lock()).create_callback(Box::new(move |rawlua, nargs| unsafe {
+            let state = rawlua.state();
+            let args = StackArgs::new(rawlua, state, nargs);
+            func(rawlua.lua(), args)?.push_into_specified_stack_multi(rawlua, state)
+        }))
+    }
+
     /// Same as ``create_function`` but with an added continuation function.
     ///
     /// The values passed to the continuation will be the yielded arguments
@@ -1438,6 +2268,124 @@ impl Lua {
         })
     }
 
+    /// Wraps a Rust function or closure which is not required to be `Send`, confining it to the
+    /// current thread.
+    ///
+    /// This allows a Lua instance with the `send` feature enabled to host a few callbacks that
+    /// capture thread-local data. The function can be called only from the thread it was created
+    /// on, calling it from any other thread raises a runtime error. If the function is dropped
+    /// on another thread, the closure is leaked instead.
+    ///
+    /// Without the `send` feature this is equivalent to [`Lua::create_function`] with the
+    /// thread check.
+    pub fn create_function_local<F, A, R>(&self, func: F) -> Result<Function>
+    where
+        F: Fn(&Lua, A) -> Result<R> + 'static,
+        A: FromLuaMulti,
+        R: IntoLuaMulti,
+    {
+        let func = ThreadConfined::new(func);
+        self.create_function(move |lua, args| func.get()?(lua, args))
+    }
+
+    /// Wraps a Rust function or closure, checking the argument types against `signature` before
+    /// calling it.
+    ///
+    /// Each entry of `signature` is a Lua type name describing the argument at the same position:
+    /// `nil`, `boolean`, `number`, `integer`, `string`, `table`, `function`, `thread`, `userdata`,
+    /// `lightuserdata`, `buffer`, `vector` or `any`. A trailing `?` (eg. `number?`) makes the
+    /// argument optional, accepting `nil` or no value. Arguments past the end of the signature are
+    /// not checked.
+    ///
+    /// On mismatch, an [`Error::BadArgument`] is raised with a message in the style of the Lua
+    /// standard library (eg. "bad argument #1 to `f`: number expected, got string"), before any
+    /// conversion to `A` takes place.
+    ///
+    /// Returns an error if `signature` contains an unknown type name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mluau::{Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// # let lua = Lua::new();
+    /// let signature = ["string", "integer?"];
+    /// let repeat = lua.create_typed_function(&signature, |_, (s, n): (String, Option<usize>)| {
+    ///     Ok(s.repeat(n.unwrap_or(1)))
+    /// })?;
+    /// assert_eq!(repeat.call::<String>(("ab", 2))?, "abab");
+    /// assert!(repeat.call::<String>(2).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_typed_function<F, A, R>(&self, signature: &[&str], func: F) -> Result<Function>
+    where
+        F: Fn(&Lua, A) -> Result<R> + MaybeSend + 'static,
+        A: FromLuaMulti,
+        R: IntoLuaMulti,
+    {
+        const TYPE_NAMES: &[&str] = &[
+            "any",
+            "nil",
+            "boolean",
+            "number",
+            "integer",
+            "string",
+            "table",
+            "function",
+            "thread",
+            "userdata",
+            "lightuserdata",
+            "buffer",
+            "vector",
+        ];
+
+        let signature = (signature.iter())
+            .map(|&ty| {
+                let (name, optional) = match ty.strip_suffix('?') {
+                    Some(name) => (name, true),
+                    None => (ty, false),
+                };
+                if !TYPE_NAMES.contains(&name) {
+                    let msg = format!("unknown type `{ty}` in function signature");
+                    return Err(Error::runtime(msg));
+                }
+                Ok((name.to_string(), optional))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.create_function(move |lua, args: MultiValue| {
+            for (i, (expected, optional)) in signature.iter().enumerate() {
+                let value = args.get(i);
+                let valid = match value {
+                    None | Some(Value::Nil) if *optional => true,
+                    None => false,
+                    Some(value) => match expected.as_str() {
+                        "any" => true,
+                        "integer" => match value {
+                            Value::Integer(_) => true,
+                            Value::Number(n) => n.fract() == 0.0,
+                            #[cfg(feature = "luau")]
+                            Value::Int64(_) => true,
+                            _ => false,
+                        },
+                        ty => value.type_name() == ty,
+                    },
+                };
+                if !valid {
+                    let got = value.map(|v| v.type_name()).unwrap_or("no value");
+                    return Err(Error::BadArgument {
+                        to: lua.frame_info(0).and_then(|info| info.name),
+                        pos: i + 1,
+                        name: None,
+                        cause: std::sync::Arc::new(Error::runtime(format!("{expected} expected, got {got}"))),
+                    });
+                }
+            }
+            func(lua, A::from_lua_multi(args, lua)?)
+        })
+    }
+
     /// Same as ``create_function`` but with an added ``debugname``
     #[cfg(feature = "luau")]
     pub fn create_function_with_debug<F, A, R>(
@@ -1460,6 +2408,36 @@ impl Lua {
         )
     }
 
+    /// Same as ``create_function`` but with a ``debugname`` derived from the type of ``func``.
+    ///
+    /// The name is the function (or the function enclosing the closure) name without module
+    /// paths, so callbacks show up in tracebacks with a meaningful name instead of `?`.
+    #[cfg(feature = "luau")]
+    pub fn create_function_auto<F, A, R>(&self, func: F) -> Result<Function>
+    where
+        F: Fn(&Lua, A) -> Result<R> + MaybeSend + 'static,
+        A: FromLuaMulti,
+        R: IntoLuaMulti,
+    {
+        use std::collections::BTreeMap;
+        use std::ffi::CString;
+        use std::sync::Mutex;
+
+        // Luau keeps a raw pointer to the debug name, so the names are interned for the lifetime
+        // of the program (there is a finite number of them)
+        static DEBUG_NAMES: Mutex<BTreeMap<&'static str, &'static CStr>> = Mutex::new(BTreeMap::new());
+
+        let name = crate::util::short_function_name::<F>();
+        let debugname = {
+            let mut names = DEBUG_NAMES.lock().unwrap_or_else(|err| err.into_inner());
+            *names.entry(name).or_insert_with(|| {
+                let name = CString::new(name).unwrap_or_default();
+                Box::leak(name.into_boxed_c_str())
+            })
+        };
+        self.create_function_with_debug(func, Some(debugname))
+    }
+
     /// Same as ``create_function_mut`` but with an added ``debugname``
     #[cfg(feature = "luau")]
     pub fn create_function_mut_with_debug<F, A, R>(
@@ -1517,6 +2495,35 @@ impl Lua {
         unsafe { self.lock().create_thread(&func) }
     }
 
+    /// Wraps a Lua function into a thread, reusing a previously released thread if available.
+    ///
+    /// Behaves like [`Lua::create_thread`], but takes the thread from an internal pool filled by
+    /// [`Lua::release_thread`]. This is useful for schedulers that run many short-lived coroutines.
+    pub fn acquire_thread(&self, func: Function) -> Result<Thread> {
+        unsafe { self.lock().acquire_thread(&func) }
+    }
+
+    /// Returns a thread to the internal pool so it can be reused by [`Lua::acquire_thread`].
+    ///
+    /// The thread is reset before being stored, and its hook and thread data are removed. It's
+    /// recycled only if it was taken from [`Lua::acquire_thread`], this is the last handle to it,
+    /// it has not been passed to Lua, it can be reset (see [`Thread::reset`]) and the pool is not
+    /// full (see [`Lua::set_thread_pool_size`]); otherwise it's simply dropped. Returns `true` if
+    /// the thread was recycled.
+    ///
+    /// The thread body must not keep references to its own thread (e.g. from
+    /// `coroutine.running`), as they would refer to the recycled thread.
+    pub fn release_thread(&self, thread: Thread) -> bool {
+        unsafe { self.lock().release_thread(thread) }
+    }
+
+    /// Sets the maximum number of threads kept in the pool used by [`Lua::acquire_thread`].
+    ///
+    /// Threads above the new limit are dropped. Default: 16
+    pub fn set_thread_pool_size(&self, size: usize) {
+        unsafe { self.lock().set_thread_pool_limit(size) }
+    }
+
     /// Creates a Lua userdata object from a custom userdata type.
     ///
     /// All userdata instances of the same type `T` shares the same metatable.
@@ -1588,6 +2595,66 @@ impl Lua {
         Ok(())
     }
 
+    /// Sets the order in which userdata destructors run when the Lua instance is dropped.
+    ///
+    /// Userdata of the listed types created after this call are tracked, and when the last handle
+    /// to the Lua instance is dropped, they are destroyed type by type in the given order
+    /// (before the remaining values are collected in an unspecified order).
+    ///
+    /// This is useful for resources that must be torn down in a specific order, e.g. database
+    /// handles before their connection pool.
+    ///
+    /// See also [`Lua::run_finalizers_in_order`].
+    pub fn set_userdata_destructor_order(&self, types: &[TypeId]) -> Result<()> {
+        let lua = self.lock();
+        unsafe {
+            let extra = lua.extra.get();
+            if (*extra).userdata_instances.is_none() {
+                let instances = self.create_table()?;
+                let mt = self.create_table_from([("__mode", "k")])?;
+                instances.set_metatable(Some(mt))?;
+                (*extra).userdata_instances = Some(instances);
+            }
+            (*extra).userdata_dtor_order = types.to_vec();
+        }
+        Ok(())
+    }
+
+    /// Destroys live userdata of the given types, type by type in the given order.
+    ///
+    /// Only userdata tracked after calling [`Lua::set_userdata_destructor_order`] with their types
+    /// can be found, an error is returned (before destroying anything) if some of the `types` are
+    /// not tracked. Destroyed userdata become unusable, as if [`AnyUserData::destroy`] was called.
+    /// Userdata that are currently borrowed are skipped.
+    pub fn run_finalizers_in_order(&self, types: &[TypeId]) -> Result<()> {
+        let instances = {
+            let lua = self.lock();
+            let extra = unsafe { &*lua.extra.get() };
+            if let Some(type_id) = types.iter().find(|t| !extra.userdata_dtor_order.contains(t)) {
+                let msg = format!("userdata type {type_id:?} is not tracked for finalization");
+                return Err(Error::runtime(msg));
+            }
+            match &extra.userdata_instances {
+                Some(instances) => instances.clone(),
+                None => return Ok(()),
+            }
+        };
+
+        for &type_id in types {
+            let userdata = instances
+                .pairs::<AnyUserData, Value>()
+                .filter_map(|pair| pair.ok().map(|(ud, _)| ud))
+                .filter(|ud| ud.type_id() == Some(type_id))
+                .collect::<Vec<_>>();
+            for ud in userdata {
+                if ud.destroy().is_ok() {
+                    instances.raw_remove(ud)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Creates a new dynamic userdata type.
     ///
     /// This is useful for when you do not have a type `T` known at compile time,
@@ -1611,6 +2678,28 @@ impl Lua {
         }
     }
 
+    /// Enables or disables userdata borrow diagnostics.
+    ///
+    /// When enabled, userdata methods record the method name while holding a borrow of the
+    /// userdata object. If another method then fails to borrow the same object (eg. because the
+    /// first method re-entered Lua), the resulting [`UserDataBorrowError`] or
+    /// [`UserDataBorrowMutError`] is annotated with the name of the method holding the
+    /// outstanding borrow.
+    ///
+    /// This adds some overhead to every userdata method call, so it's disabled by default.
+    ///
+    /// [`UserDataBorrowError`]: crate::Error::UserDataBorrowError
+    /// [`UserDataBorrowMutError`]: crate::Error::UserDataBorrowMutError
+    pub fn enable_borrow_diagnostics(&self, enabled: bool) {
+        let lua = self.lock();
+        let extra = unsafe { &mut *lua.extra.get() };
+        match (enabled, &extra.borrow_diagnostics) {
+            (true, None) => extra.borrow_diagnostics = Some(Default::default()),
+            (false, _) => extra.borrow_diagnostics = None,
+            _ => {}
+        }
+    }
+
     /// Create a Lua userdata "proxy" object from a custom userdata type.
     ///
     /// Proxy object is an empty userdata object that has `T` metatable attached.
@@ -1714,13 +2803,31 @@ impl Lua {
         let lua = self.lock();
         let state = lua.state();
         unsafe {
+            let extra = lua.extra.get();
+            if let Some(globals) = &(*extra).globals_cache {
+                // In Lua 5.2+ globals table is shared, and can be changed only by `set_globals`.
+                // Otherwise each thread has its own globals table, so check that it matches.
+                #[cfg(any(feature = "lua54", feature = "lua53", feature = "lua52"))]
+                return globals.clone();
+                #[cfg(any(feature = "lua51", feature = "luajit", feature = "luau"))]
+                {
+                    let ref_thread = lua.ref_thread(globals.0.aux_thread);
+                    let globals_ptr = ffi::lua_topointer(ref_thread, globals.0.index);
+                    if ffi::lua_topointer(state, ffi::LUA_GLOBALSINDEX) == globals_ptr {
+                        return globals.clone();
+                    }
+                }
+            }
+
             let _sg = StackGuard::new(state);
             assert_stack(state, 1);
             #[cfg(any(feature = "lua54", feature = "lua53", feature = "lua52"))]
             ffi::lua_rawgeti(state, ffi::LUA_REGISTRYINDEX, ffi::LUA_RIDX_GLOBALS);
             #[cfg(any(feature = "lua51", feature = "luajit", feature = "luau"))]
             ffi::lua_pushvalue(state, ffi::LUA_GLOBALSINDEX);
-            Table(lua.pop_ref())
+            let globals = Table(lua.pop_ref());
+            (*extra).globals_cache = Some(globals.clone());
+            globals
         }
     }
 
@@ -1752,11 +2859,60 @@ impl Lua {
             ffi::lua_rawseti(state, ffi::LUA_REGISTRYINDEX, ffi::LUA_RIDX_GLOBALS);
             #[cfg(any(feature = "lua51", feature = "luajit", feature = "luau"))]
             ffi::lua_replace(state, ffi::LUA_GLOBALSINDEX);
+
+            (*lua.extra.get()).globals_cache = Some(globals);
         }
 
         Ok(())
     }
 
+    /// Temporarily sets the global variable `name` to `value` while running `f`.
+    ///
+    /// The previous value of the global is restored when `f` returns, even if it returns an error
+    /// or panics. Raw access is used to read and restore the variable, bypassing metamethods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mluau::{Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// let print = lua.create_function(|_, _: ()| Ok(()))?;
+    /// lua.with_global("print", print, || lua.load("print('muted')").exec())?;
+    /// assert!(lua.globals().get::<mluau::Function>("print").is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_global<R>(
+        &self,
+        name: &str,
+        value: impl IntoLua,
+        f: impl FnOnce() -> Result<R>,
+    ) -> Result<R> {
+        struct RestoreGuard<'a> {
+            globals: Table,
+            name: &'a str,
+            value: Option<Value>,
+        }
+
+        impl Drop for RestoreGuard<'_> {
+            fn drop(&mut self) {
+                let value = self.value.take().unwrap_or(Value::Nil);
+                let _ = self.globals.raw_set(self.name, value);
+            }
+        }
+
+        let globals = self.globals();
+        let old_value = globals.raw_get::<Value>(name)?;
+        globals.raw_set(name, value)?;
+        let _guard = RestoreGuard {
+            globals,
+            name,
+            value: Some(old_value),
+        };
+        f()
+    }
+
     /// Returns a handle to the active `Thread`.
     ///
     /// For calls to `Lua` this will be the main Lua thread, for parameters given to a callback,
@@ -1772,6 +2928,15 @@ impl Lua {
         }
     }
 
+    /// Returns `true` if the active thread is the main Lua thread.
+    ///
+    /// Outside of callbacks this is always `true`. Inside a callback it reports whether the
+    /// callback was invoked from the main thread or from a coroutine.
+    pub fn is_main_thread(&self) -> bool {
+        let lua = self.lock();
+        lua.state() == lua.main_state()
+    }
+
     /// Attempts to coerce a Lua value into a String in a manner consistent with Lua's internal
     /// behavior.
     ///
@@ -1856,6 +3021,47 @@ impl Lua {
         })
     }
 
+    /// Converts a string to a number following Lua's own numeric parsing rules.
+    ///
+    /// Unlike [`str::parse`], this accepts everything Lua's `tonumber` accepts (eg. hexadecimal
+    /// numbers and leading/trailing whitespace). On Lua 5.3/5.4 the result is a [`Value::Integer`]
+    /// or [`Value::Number`] depending on the string contents, on other versions it is always a
+    /// [`Value::Number`].
+    ///
+    /// Returns `None` if the string is not a valid numeral.
+    pub fn string_to_number(&self, s: impl AsRef<[u8]>) -> Result<Option<Value>> {
+        let s = s.as_ref();
+        let lua = self.lock();
+        let state = lua.state();
+        unsafe {
+            let _sg = StackGuard::new(state);
+            check_stack(state, 2)?;
+
+            #[cfg(any(feature = "lua54", feature = "lua53"))]
+            {
+                let Ok(s) = std::ffi::CString::new(s) else {
+                    return Ok(None);
+                };
+                if ffi::lua_stringtonumber(state, s.as_ptr()) == 0 {
+                    return Ok(None);
+                }
+                if ffi::lua_isinteger(state, -1) != 0 {
+                    Ok(Some(Value::Integer(ffi::lua_tointeger(state, -1))))
+                } else {
+                    Ok(Some(Value::Number(ffi::lua_tonumber(state, -1))))
+                }
+            }
+
+            #[cfg(not(any(feature = "lua54", feature = "lua53")))]
+            {
+                push_string(state, s, !lua.unlikely_memory_error())?;
+                let mut isnum = 0;
+                let n = ffi::lua_tonumberx(state, -1, &mut isnum);
+                Ok((isnum != 0).then_some(Value::Number(n)))
+            }
+        }
+    }
+
     /// Converts a value that implements [`IntoLua`] into a [`Value`] instance.
     #[inline]
     pub fn pack(&self, t: impl IntoLua) -> Result<Value> {
@@ -2015,6 +3221,54 @@ impl Lua {
         Ok(())
     }
 
+    /// Places a value in the Lua registry and returns a [`LightRef`] to it.
+    ///
+    /// This is a thin wrapper around `luaL_ref` intended for code that manages a large number of
+    /// short-lived anchors and does its own lifetime bookkeeping. The reference is never released
+    /// automatically, it must be freed with [`Lua::drop_light_ref`].
+    pub fn create_light_ref(&self, v: &Value) -> Result<LightRef> {
+        let lua = self.lock();
+        let state = lua.state();
+        unsafe {
+            let _sg = StackGuard::new(state);
+            check_stack(state, 4)?;
+
+            lua.push_value_at(v, state)?;
+            let id = if lua.unlikely_memory_error() {
+                ffi::luaL_ref(state, ffi::LUA_REGISTRYINDEX)
+            } else {
+                protect_lua!(state, 1, 0, |state| {
+                    ffi::luaL_ref(state, ffi::LUA_REGISTRYINDEX)
+                })?
+            };
+            Ok(LightRef(id))
+        }
+    }
+
+    /// Gets a value from the Lua registry by its [`LightRef`].
+    pub fn light_ref_value<T: FromLua>(&self, r: &LightRef) -> Result<T> {
+        let lua = self.lock();
+        let state = lua.state();
+        match r.id() {
+            ffi::LUA_REFNIL => T::from_lua(Value::Nil, self),
+            id => unsafe {
+                let _sg = StackGuard::new(state);
+                check_stack(state, 1)?;
+
+                ffi::lua_rawgeti(state, ffi::LUA_REGISTRYINDEX, id as Integer);
+                T::from_specified_stack(-1, &lua, state)
+            },
+        }
+    }
+
+    /// Releases a registry slot previously obtained with [`Lua::create_light_ref`].
+    ///
+    /// The reference is consumed, and the slot may be reused by subsequent references.
+    pub fn drop_light_ref(&self, r: LightRef) {
+        let lua = self.lock();
+        unsafe { ffi::luaL_unref(lua.state(), ffi::LUA_REGISTRYINDEX, r.id()) };
+    }
+
     /// Replaces a value in the Lua registry by its [`RegistryKey`].
     ///
     /// An identifier used in [`RegistryKey`] may possibly be changed to a new value.
@@ -2285,6 +3539,10 @@ impl Lua {
     }
 
     /// Checks if Lua is be allowed to yield.
+    ///
+    /// Returns `true` if the currently running thread is a coroutine that can yield from here,
+    /// eg. a Rust callback called inside of a coroutine. Callbacks can use this to fall back to a
+    /// non-yielding path instead of failing when [`Lua::yield_with`] is not possible.
     #[cfg(not(any(feature = "lua51", feature = "lua52", feature = "luajit")))]
     #[inline]
     pub fn is_yieldable(&self) -> bool {
@@ -2526,6 +3784,58 @@ impl Deref for LuaGuard {
     }
 }
 
+#[cfg(feature = "luau")]
+unsafe extern "C-unwind" fn interrupt_proc(state: *mut ffi::lua_State, gc: c_int) {
+    if gc >= 0 {
+        // GC interrupts cannot survive Lua exceptions and hence abort if they throw
+        let extra = ExtraData::get(state);
+        if let Some(callback) = &(*extra).gc_interrupt_callback {
+            use std::panic::{catch_unwind, AssertUnwindSafe};
+            use std::process::abort;
+
+            if XRc::strong_count(&callback) > 2 {
+                return; // Don't allow recursion
+            }
+            (*extra).running_gc = true;
+            match catch_unwind(AssertUnwindSafe(|| (callback)((*extra).lua(), gc))) {
+                Ok(_) => {}
+                Err(_) => abort(),
+            };
+            (*extra).running_gc = false;
+        }
+        return;
+    }
+    let result = callback_error_ext(state, ptr::null_mut(), false, move |extra, _| {
+        if (*extra).track_interrupt_line {
+            let mut ar: ffi::lua_Debug = mem::zeroed();
+            if ffi::lua_getinfo(state, 0, cstr!("l"), &mut ar) != 0 {
+                (*extra).raw_lua().publish_line(ar.currentline);
+            }
+        }
+        if let Some(max_depth) = (*extra).max_call_depth {
+            if ffi::lua_stackdepth(state) as u32 > max_depth {
+                return Err(Error::StackError);
+            }
+        }
+        let Some(interrupt_cb) = (*extra).interrupt_callback.clone() else {
+            return Ok(VmState::Continue);
+        };
+        if XRc::strong_count(&interrupt_cb) > 2 {
+            return Ok(VmState::Continue); // Don't allow recursion
+        }
+        interrupt_cb((*extra).lua())
+    });
+    match result {
+        VmState::Continue => {}
+        VmState::Yield => {
+            // We can yield only at yieldable points, otherwise ignore and continue
+            if ffi::lua_isyieldable(state) != 0 {
+                ffi::lua_yield(state, 0);
+            }
+        }
+    }
+}
+
 pub(crate) mod extra;
 mod raw;
 pub(crate) mod util;
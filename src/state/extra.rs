@@ -11,10 +11,12 @@ use rustc_hash::FxHashMap;
 #[cfg(feature = "dynamic-userdata")]
 use rustc_hash::FxHashSet;
 
+use crate::chunk::SourceMaps;
 use crate::error::Result;
 use crate::state::RawLua;
 use crate::stdlib::StdLib;
-use crate::types::{AppData, ReentrantMutex, XRc};
+use crate::table::Table;
+use crate::types::{AppData, ReentrantMutex, XRc, XWeak};
 
 use crate::userdata::RawUserDataRegistry;
 use crate::util::{get_internal_metatable, push_internal_userdata, TypeKey, WrappedFailure};
@@ -29,6 +31,7 @@ use super::{Lua, WeakLua};
 static EXTRA_REGISTRY_KEY: u8 = 0;
 
 const WRAPPED_FAILURE_POOL_DEFAULT_CAPACITY: usize = 64;
+const THREAD_POOL_DEFAULT_CAPACITY: usize = 16;
 pub const REF_STACK_RESERVE: c_int = 3;
 
 pub(crate) struct RefThread {
@@ -84,6 +87,13 @@ pub(crate) struct ExtraData {
     #[cfg(feature = "dynamic-userdata")]
     pub(crate) dyn_userdata_set: FxHashSet<*mut c_void>,
 
+    // Methods currently holding a userdata borrow (when borrow diagnostics are enabled)
+    pub(crate) borrow_diagnostics: Option<FxHashMap<*const c_void, Vec<String>>>,
+
+    // Buffers currently viewed as typed slices: a positive count of shared views, or -1 for
+    // a mutable view
+    pub(crate) buffer_borrows: FxHashMap<*const u8, isize>,
+
     // When Lua instance dropped, setting `None` would prevent collecting `RegistryKey`s
     pub(super) registry_unref_list: Arc<Mutex<Option<Vec<c_int>>>>,
 
@@ -108,12 +118,47 @@ pub(crate) struct ExtraData {
     // Address of `WrappedFailure` metatable
     pub(super) wrapped_failure_mt_ptr: *const c_void,
 
+    // Pool of reset threads (as `(aux_thread, index)` slots in the ref threads)
+    pub(super) thread_pool: Vec<(usize, c_int)>,
+    // Maximum number of threads kept in the pool
+    pub(super) thread_pool_limit: usize,
+    // Threads handed out by `Lua::acquire_thread` and never passed to Lua, by their handle
+    pub(crate) pool_owned_threads: FxHashMap<*mut ffi::lua_State, XWeak<c_int>>,
+
+    // Cached handle to the globals table (see `Lua::globals`)
+    pub(super) globals_cache: Option<Table>,
+
+    // Environment used for chunks loaded without an explicit one
+    pub(super) default_chunk_env: Option<Table>,
+
+    // Userdata types to destroy (in order) on close, and their tracked instances
+    pub(super) userdata_dtor_order: Vec<TypeId>,
+    pub(crate) userdata_instances: Option<Table>,
+
+    // Source maps of live chunks (see `Chunk::set_source_map`)
+    pub(crate) source_maps: SourceMaps,
+
+    // Whether interrupts publish the current line (see `Lua::track_interrupt_line`)
+    #[cfg(feature = "luau")]
+    pub(super) track_interrupt_line: bool,
+
+    // Diagnostic label of this Lua instance
+    pub(super) name: Option<String>,
+
+    // Maximum depth of the call stack enforced by hooks (or interrupts in Luau)
+    pub(super) max_call_depth: Option<u32>,
+
+    // Lua 5.1 does not expose the GC running state, so track it ourselves
+    #[cfg(any(feature = "lua51", feature = "luajit"))]
+    pub(super) gc_stopped: bool,
+
     #[cfg(not(feature = "luau"))]
     pub(super) hook_callback: Option<crate::types::HookCallback>,
     #[cfg(not(feature = "luau"))]
     pub(super) hook_triggers: crate::debug::HookTriggers,
     #[cfg(feature = "lua54")]
     pub(super) warn_callback: Option<crate::types::WarnCallback>,
+    pub(super) panic_handler: Option<crate::types::PanicHandler>,
     #[cfg(feature = "luau")]
     pub(super) interrupt_callback: Option<crate::types::InterruptCallback>,
     #[cfg(feature = "luau")]
@@ -141,6 +186,9 @@ pub(crate) struct ExtraData {
     #[cfg(not(feature = "lua51"))]
     pub(super) yielded_values: Option<MultiValue>,
 
+    // Cause of the callback error last returned by `Lua::raise`, passed through without rewrapping
+    pub(crate) reraised_error: Option<std::sync::Weak<crate::error::Error>>,
+
     // Callback called when lua VM is about to be closed
     #[cfg(feature = "send")]
     pub(super) on_close: Option<Box<dyn Fn() + Send + 'static>>,
@@ -153,6 +201,17 @@ pub(crate) struct ExtraData {
 
 impl Drop for ExtraData {
     fn drop(&mut self) {
+        // The ref thread can be already closed at this point, so don't release the cached slots
+        if let Some(mut globals) = self.globals_cache.take() {
+            globals.0.index_count = None;
+        }
+        if let Some(mut instances) = self.userdata_instances.take() {
+            instances.0.index_count = None;
+        }
+        if let Some(mut env) = self.default_chunk_env.take() {
+            env.0.index_count = None;
+        }
+
         unsafe {
             if !self.owned {
                 self.lua.assume_init_drop();
@@ -198,6 +257,8 @@ impl ExtraData {
             last_checked_userdata_mt: (ptr::null(), None),
             #[cfg(feature = "dynamic-userdata")]
             dyn_userdata_set: FxHashSet::default(),
+            borrow_diagnostics: None,
+            buffer_borrows: FxHashMap::default(),
             registry_unref_list: Arc::new(Mutex::new(Some(Vec::new()))),
             app_data: AppData::default(),
             app_data_priv: AppData::default(),
@@ -209,12 +270,27 @@ impl ExtraData {
             wrapped_failure_pool: Vec::with_capacity(WRAPPED_FAILURE_POOL_DEFAULT_CAPACITY),
             wrapped_failure_top: 0,
             wrapped_failure_mt_ptr,
+            thread_pool: Vec::with_capacity(THREAD_POOL_DEFAULT_CAPACITY),
+            thread_pool_limit: THREAD_POOL_DEFAULT_CAPACITY,
+            pool_owned_threads: FxHashMap::default(),
+            globals_cache: None,
+            default_chunk_env: None,
+            source_maps: SourceMaps::default(),
+            userdata_dtor_order: Vec::new(),
+            userdata_instances: None,
+            name: None,
+            max_call_depth: None,
+            #[cfg(feature = "luau")]
+            track_interrupt_line: false,
+            #[cfg(any(feature = "lua51", feature = "luajit"))]
+            gc_stopped: false,
             #[cfg(not(feature = "luau"))]
             hook_callback: None,
             #[cfg(not(feature = "luau"))]
             hook_triggers: Default::default(),
             #[cfg(feature = "lua54")]
             warn_callback: None,
+            panic_handler: None,
             #[cfg(feature = "luau")]
             interrupt_callback: None,
             #[cfg(feature = "luau")]
@@ -235,6 +311,7 @@ impl ExtraData {
             running_gc: false,
             #[cfg(not(feature = "lua51"))]
             yielded_values: None,
+            reraised_error: None,
             disable_error_userdata: false,
             on_close: None,
             #[cfg(feature = "luau")]
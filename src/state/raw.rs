@@ -8,6 +8,7 @@ use std::os::raw::{c_char, c_int, c_void};
 use std::panic::resume_unwind;
 use std::ptr::{self, NonNull};
 use std::string::String as StdString;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
 use crate::chunk::ChunkMode;
@@ -24,7 +25,7 @@ use crate::thread::Thread;
 use crate::traits::IntoLua;
 use crate::types::{
     AppDataRef, AppDataRefMut, Callback, CallbackUpvalue, DestructedUserdata, Integer, LightUserData,
-    LuaType, MaybeSend, ReentrantMutex, RegistryKey, ValueRef, XRc,
+    LuaType, MaybeSend, ReentrantMutex, RegistryKey, ThreadErrorHandler, ValueRef, XRc,
 };
 
 #[cfg(feature = "luau")]
@@ -53,9 +54,13 @@ use super::{Lua, LuaOptions, WeakLua};
 #[cfg(not(feature = "luau"))]
 use crate::{
     debug::Debug,
-    types::{HookCallback, HookKind, VmState},
+    types::{HookKind, ThreadHook, VmState},
 };
 
+// Key to store hooks in the registry
+#[cfg(not(feature = "luau"))]
+const HOOKS_KEY: *const c_char = cstr!("__mlua_hooks");
+
 /// An inner Lua struct which holds a raw Lua state.
 #[doc(hidden)]
 pub struct RawLua {
@@ -63,6 +68,8 @@ pub struct RawLua {
     pub(super) state: Cell<*mut ffi::lua_State>,
     pub(super) main_state: Option<NonNull<ffi::lua_State>>,
     pub(super) extra: XRc<UnsafeCell<ExtraData>>,
+    // Last line published by hooks or interrupts, readable without locking (0 if unknown)
+    pub(super) current_line: XRc<AtomicU32>,
     owned: bool,
 }
 
@@ -149,6 +156,11 @@ impl RawLua {
         self.extra.get()
     }
 
+    #[inline]
+    pub(super) fn publish_line(&self, line: c_int) {
+        self.current_line.store(line.max(0) as u32, Ordering::Relaxed);
+    }
+
     pub(super) unsafe fn new(libs: StdLib, options: &LuaOptions) -> XRc<ReentrantMutex<Self>> {
         Self::new_ext(libs, options, true)
     }
@@ -238,7 +250,8 @@ impl RawLua {
                 #[cfg(feature = "luau")]
                 init_internal_metatable::<NamecallMapUpvalue>(state, None)?;
                 #[cfg(not(feature = "luau"))]
-                init_internal_metatable::<HookCallback>(state, None)?;
+                init_internal_metatable::<ThreadHook>(state, None)?;
+                init_internal_metatable::<ThreadErrorHandler>(state, None)?;
 
                 // Init serde metatables
                 #[cfg(feature = "serde")]
@@ -273,6 +286,7 @@ impl RawLua {
             // Make sure that we don't store current state as main state (if it's not available)
             main_state: get_main_state(state).and_then(NonNull::new),
             extra: XRc::clone(&extra),
+            current_line: XRc::new(AtomicU32::new(0)),
             owned,
         }));
         (*extra.get()).set_lua(&rawlua);
@@ -381,6 +395,7 @@ impl RawLua {
             let _sg = StackGuard::new(state);
             check_stack(state, 3)?;
 
+            let chunk_name = name;
             let name = name.map(CStr::as_ptr).unwrap_or(ptr::null());
             let mode = match mode {
                 Some(ChunkMode::Binary) => cstr!("b"),
@@ -396,12 +411,71 @@ impl RawLua {
                 })?
             };
             match status {
-                ffi::LUA_OK => Ok(Function(self.pop_ref())),
-                err => Err(pop_error(state, err)),
+                ffi::LUA_OK => {
+                    #[cfg(feature = "luau")]
+                    {
+                        use crate::function::MAIN_CHUNKS_KEY;
+
+                        // Luau reports main chunks as regular Lua functions, so we mark them here
+                        ffi::lua_pushboolean(state, 1);
+                        self.set_function_data(state, MAIN_CHUNKS_KEY)?;
+                    }
+                    Ok(Function(self.pop_ref()))
+                }
+                err => match pop_error(state, err) {
+                    Error::SyntaxError {
+                        message,
+                        incomplete_input,
+                        chunk_name: None,
+                    } => Err(Error::SyntaxError {
+                        message,
+                        incomplete_input,
+                        chunk_name: chunk_name.map(|name| name.to_string_lossy().into_owned()),
+                    }),
+                    err => Err(err),
+                },
             }
         }
     }
 
+    /// Associates bytecode with the function, see [`Function::bytecode`].
+    #[cfg(feature = "luau")]
+    pub(crate) unsafe fn set_function_bytecode(&self, func: &Function, bytecode: &[u8]) -> Result<()> {
+        use crate::function::FUNCTION_BYTECODE_KEY;
+
+        let state = self.state();
+        let _sg = StackGuard::new(state);
+        check_stack(state, 2)?;
+
+        self.push_ref_at(&func.0, state);
+        ffi::lua_pushlstring(state, bytecode.as_ptr() as *const c_char, bytecode.len());
+        self.set_function_data(state, FUNCTION_BYTECODE_KEY)
+    }
+
+    /// Associates the value on top of the stack with the function below it and pops the value.
+    ///
+    /// The value is stored in a weak-keyed registry table, so it's released with the function.
+    #[cfg(feature = "luau")]
+    unsafe fn set_function_data(&self, state: *mut ffi::lua_State, key: *const c_char) -> Result<()> {
+        check_stack(state, 4)?;
+        protect_lua!(state, 2, 1, |state| {
+            if ffi::lua_getfield(state, ffi::LUA_REGISTRYINDEX, key) != ffi::LUA_TTABLE {
+                ffi::lua_pop(state, 1);
+                ffi::lua_createtable(state, 0, 0);
+                ffi::lua_createtable(state, 0, 1);
+                ffi::lua_pushstring(state, cstr!("k"));
+                ffi::lua_setfield(state, -2, cstr!("__mode"));
+                ffi::lua_setmetatable(state, -2);
+                ffi::lua_pushvalue(state, -1);
+                ffi::lua_setfield(state, ffi::LUA_REGISTRYINDEX, key);
+            }
+            ffi::lua_pushvalue(state, -3);
+            ffi::lua_pushvalue(state, -3);
+            ffi::lua_rawset(state, -3);
+            ffi::lua_pop(state, 2);
+        })
+    }
+
     pub(crate) unsafe fn load_chunk_inner(
         &self,
         state: *mut ffi::lua_State,
@@ -440,9 +514,6 @@ impl RawLua {
         thread_state: *mut ffi::lua_State,
         hook: HookKind,
     ) -> Result<()> {
-        // Key to store hooks in the registry
-        const HOOKS_KEY: *const c_char = cstr!("__mlua_hooks");
-
         unsafe fn process_status(state: *mut ffi::lua_State, event: c_int, status: VmState) {
             match status {
                 VmState::Continue => {}
@@ -463,14 +534,75 @@ impl RawLua {
             }
         }
 
+        // Returns the `lua_sethook` mask that enables the given hook event
+        fn event_mask(event: c_int) -> c_int {
+            match event {
+                ffi::LUA_HOOKCALL => ffi::LUA_MASKCALL,
+                ffi::LUA_HOOKRET => ffi::LUA_MASKRET,
+                ffi::LUA_HOOKLINE => ffi::LUA_MASKLINE,
+                ffi::LUA_HOOKCOUNT => ffi::LUA_MASKCOUNT,
+                // `LUA_HOOKTAILRET` in Lua 5.1
+                #[cfg(any(feature = "lua51", feature = "luajit"))]
+                _ => ffi::LUA_MASKRET,
+                #[cfg(not(any(feature = "lua51", feature = "luajit")))]
+                _ => ffi::LUA_MASKCALL,
+            }
+        }
+
+        // Returns `true` if entering a function exceeds the call depth limit
+        unsafe fn call_depth_exceeded(
+            extra: *mut ExtraData,
+            state: *mut ffi::lua_State,
+            event: c_int,
+        ) -> bool {
+            match (*extra).max_call_depth {
+                // The stack has more than `max_depth` levels if the level `max_depth` exists
+                Some(max_depth) if event_mask(event) == ffi::LUA_MASKCALL => {
+                    let mut ar: ffi::lua_Debug = mem::zeroed();
+                    ffi::lua_getstack(state, max_depth as c_int, &mut ar) != 0
+                }
+                _ => false,
+            }
+        }
+
+        // Returns the hook of the thread stored in the registry (null if not set)
+        unsafe fn get_thread_hook(
+            state: *mut ffi::lua_State,
+            thread_state: *mut ffi::lua_State,
+        ) -> *const ThreadHook {
+            let top = ffi::lua_gettop(state);
+            let mut thread_hook = ptr::null();
+            if ffi::lua_getfield(state, ffi::LUA_REGISTRYINDEX, HOOKS_KEY) == ffi::LUA_TTABLE {
+                ffi::lua_pushthread(thread_state);
+                ffi::lua_xmove(thread_state, state, 1);
+                if ffi::lua_rawget(state, -2) == ffi::LUA_TUSERDATA {
+                    thread_hook = get_internal_userdata::<ThreadHook>(state, -1, ptr::null());
+                }
+            }
+            ffi::lua_settop(state, top);
+            thread_hook
+        }
+
         unsafe extern "C-unwind" fn global_hook_proc(state: *mut ffi::lua_State, ar: *mut ffi::lua_Debug) {
             let status = callback_error_ext(state, ptr::null_mut(), false, move |extra, _| {
+                let event = (*ar).event;
+                if event == ffi::LUA_HOOKLINE {
+                    (*extra).raw_lua().publish_line((*ar).currentline);
+                }
+                if call_depth_exceeded(extra, state, event) {
+                    return Err(Error::StackError);
+                }
                 match (*extra).hook_callback.clone() {
+                    // The hook can be triggered by events the callback did not ask for
+                    Some(_) if (*extra).hook_triggers.mask() & event_mask(event) == 0 => {
+                        Ok(VmState::Continue)
+                    }
                     Some(hook_callback) => {
                         let rawlua = (*extra).raw_lua();
                         let debug = Debug::new(rawlua, 0, ar);
                         hook_callback((*extra).lua(), &debug)
                     }
+                    None if (*extra).max_call_depth.is_some() => Ok(VmState::Continue),
                     None => {
                         ffi::lua_sethook(state, None, 0, 0);
                         Ok(VmState::Continue)
@@ -481,40 +613,71 @@ impl RawLua {
         }
 
         unsafe extern "C-unwind" fn hook_proc(state: *mut ffi::lua_State, ar: *mut ffi::lua_Debug) {
-            let top = ffi::lua_gettop(state);
-            let mut hook_callback_ptr = ptr::null();
             ffi::luaL_checkstack(state, 3, ptr::null());
-            if ffi::lua_getfield(state, ffi::LUA_REGISTRYINDEX, HOOKS_KEY) == ffi::LUA_TTABLE {
-                ffi::lua_pushthread(state);
-                if ffi::lua_rawget(state, -2) == ffi::LUA_TUSERDATA {
-                    hook_callback_ptr = get_internal_userdata::<HookCallback>(state, -1, ptr::null());
-                }
-            }
-            ffi::lua_settop(state, top);
-            if hook_callback_ptr.is_null() {
+            let thread_hook = get_thread_hook(state, state);
+            if thread_hook.is_null() {
                 ffi::lua_sethook(state, None, 0, 0);
                 return;
             }
 
             let status = callback_error_ext(state, ptr::null_mut(), false, |extra, _| {
+                let event = (*ar).event;
+                if event == ffi::LUA_HOOKLINE {
+                    (*extra).raw_lua().publish_line((*ar).currentline);
+                }
+                if call_depth_exceeded(extra, state, event) {
+                    return Err(Error::StackError);
+                }
+                // The hook can be triggered by events the callback did not ask for
+                if (*thread_hook).triggers.mask() & event_mask(event) == 0 {
+                    return Ok(VmState::Continue);
+                }
                 let rawlua = (*extra).raw_lua();
                 let debug = Debug::new(rawlua, 0, ar);
-                let hook_callback = (*hook_callback_ptr).clone();
+                let hook_callback = (*thread_hook).callback.clone();
                 hook_callback((*extra).lua(), &debug)
             });
             process_status(state, (*ar).event, status)
         }
 
+        // Call events are needed to enforce the call depth limit
+        let depth_mask = match (*self.extra.get()).max_call_depth {
+            Some(_) => ffi::LUA_MASKCALL,
+            None => 0,
+        };
+
         let (triggers, callback) = match hook {
-            HookKind::Global if (*self.extra.get()).hook_callback.is_none() => {
-                return Ok(());
-            }
             HookKind::Global => {
-                let triggers = (*self.extra.get()).hook_triggers;
-                let (mask, count) = (triggers.mask(), triggers.count());
+                let extra = &*self.extra.get();
+                if extra.hook_callback.is_none() && depth_mask == 0 {
+                    return Ok(());
+                }
+                let triggers = extra.hook_triggers;
+                let (mask, count) = (triggers.mask() | depth_mask, triggers.count());
                 ffi::lua_sethook(thread_state, Some(global_hook_proc), mask, count);
                 return Ok(());
             }
+            HookKind::Refresh => {
+                let current_hook = ffi::lua_gethook(thread_state).map(|hook| hook as usize);
+                if current_hook == Some(hook_proc as usize) {
+                    let state = self.state();
+                    check_stack(state, 3)?;
+                    check_stack(thread_state, 1)?;
+                    let thread_hook = get_thread_hook(state, thread_state);
+                    if !thread_hook.is_null() {
+                        let triggers = (*thread_hook).triggers;
+                        let (mask, count) = (triggers.mask() | depth_mask, triggers.count());
+                        ffi::lua_sethook(thread_state, Some(hook_proc), mask, count);
+                    }
+                } else if current_hook.is_none() || current_hook == Some(global_hook_proc as usize) {
+                    if (*self.extra.get()).hook_callback.is_none() && depth_mask == 0 {
+                        ffi::lua_sethook(thread_state, None, 0, 0);
+                    } else {
+                        return self.set_thread_hook(thread_state, HookKind::Global);
+                    }
+                }
+                return Ok(());
+            }
             HookKind::Thread(triggers, callback) => (triggers, callback),
         };
 
@@ -533,15 +696,34 @@ impl RawLua {
 
             ffi::lua_pushthread(thread_state);
             ffi::lua_xmove(thread_state, state, 1); // key (thread)
-            let _ = push_internal_userdata(state, callback, false); // value (hook callback)
-            ffi::lua_rawset(state, -3); // hooktable[thread] = hook callback
+            let _ = push_internal_userdata(state, ThreadHook { triggers, callback }, false); // value (hook)
+            ffi::lua_rawset(state, -3); // hooktable[thread] = hook
         })?;
 
-        ffi::lua_sethook(thread_state, Some(hook_proc), triggers.mask(), triggers.count());
+        let (mask, count) = (triggers.mask() | depth_mask, triggers.count());
+        ffi::lua_sethook(thread_state, Some(hook_proc), mask, count);
 
         Ok(())
     }
 
+    /// Removes the hook of a thread (coroutine) together with its entry in the registry.
+    #[cfg(not(feature = "luau"))]
+    pub(crate) unsafe fn remove_thread_hook(&self, thread_state: *mut ffi::lua_State) {
+        ffi::lua_sethook(thread_state, None, 0, 0);
+
+        let state = self.state();
+        let _sg = StackGuard::new(state);
+        if ffi::lua_checkstack(state, 3) == 0 || ffi::lua_checkstack(thread_state, 1) == 0 {
+            return;
+        }
+        if ffi::lua_getfield(state, ffi::LUA_REGISTRYINDEX, HOOKS_KEY) == ffi::LUA_TTABLE {
+            ffi::lua_pushthread(thread_state);
+            ffi::lua_xmove(thread_state, state, 1); // key (thread)
+            ffi::lua_pushnil(state);
+            ffi::lua_rawset(state, -3); // hooktable[thread] = nil
+        }
+    }
+
     /// See [`Lua::create_string`]
     pub(crate) unsafe fn create_string(&self, s: &[u8]) -> Result<String> {
         let state = self.state();
@@ -672,6 +854,72 @@ impl RawLua {
         Ok(thread)
     }
 
+    /// Takes a thread from the thread pool (or creates a new one) and sets `func` as its body.
+    pub(crate) unsafe fn acquire_thread(&self, func: &Function) -> Result<Thread> {
+        let thread = match (*self.extra.get()).thread_pool.pop() {
+            Some((aux_thread, index)) => {
+                let thread_state = ffi::lua_tothread(self.ref_thread(aux_thread), index);
+                let thread = Thread(self.new_value_ref(aux_thread, index), thread_state);
+
+                // Inherit global hook if set
+                #[cfg(not(feature = "luau"))]
+                self.set_thread_hook(thread_state, HookKind::Global)?;
+
+                ffi::lua_xpush(self.ref_thread(func.0.aux_thread), thread_state, func.0.index);
+
+                #[cfg(feature = "luau")]
+                {
+                    // Inherit `LUA_GLOBALSINDEX` from the main thread
+                    ffi::lua_xpush(self.main_state(), thread_state, ffi::LUA_GLOBALSINDEX);
+                    ffi::lua_replace(thread_state, ffi::LUA_GLOBALSINDEX);
+                }
+
+                thread
+            }
+            None => self.create_thread(func)?,
+        };
+
+        // Remember the handle, only threads that are still exclusively owned by it can be released
+        if let Some(index) = &thread.0.index_count {
+            let owned = &mut (*self.extra.get()).pool_owned_threads;
+            owned.retain(|_, handle| handle.strong_count() > 0);
+            owned.insert(thread.1, XRc::downgrade(&index.0));
+        }
+
+        Ok(thread)
+    }
+
+    /// Resets the thread and puts it to the thread pool if there is room for it.
+    ///
+    /// Returns `false` if the thread was not recycled.
+    pub(crate) unsafe fn release_thread(&self, thread: Thread) -> bool {
+        let pool_full = {
+            let extra = &*self.extra.get();
+            extra.thread_pool.len() >= extra.thread_pool_limit
+        };
+        if pool_full {
+            return false;
+        }
+        match thread.into_pool_slot(self) {
+            Some(slot) => {
+                (*self.extra.get()).thread_pool.push(slot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the maximum number of threads kept in the thread pool, dropping the excess ones.
+    pub(crate) unsafe fn set_thread_pool_limit(&self, limit: usize) {
+        let extra = &mut *self.extra.get();
+        extra.thread_pool_limit = limit;
+        while extra.thread_pool.len() > limit {
+            if let Some((aux_thread, index)) = extra.thread_pool.pop() {
+                drop(self.new_value_ref(aux_thread, index));
+            }
+        }
+    }
+
     /// Pushes a primitive type value onto the Lua stack.
     pub(crate) unsafe fn push_primitive_type<T: LuaType>(&self, state: *mut ffi::lua_State) -> bool {
         match T::TYPE_ID {
@@ -742,7 +990,14 @@ impl RawLua {
             Value::String(s) => self.push_ref_at(&s.0, state),
             Value::Table(t) => self.push_ref_at(&t.0, state),
             Value::Function(f) => self.push_ref_at(&f.0, state),
-            Value::Thread(t) => self.push_ref_at(&t.0, state),
+            Value::Thread(t) => {
+                // A thread passed to Lua may be referenced from there, so it can't be recycled
+                let pool_owned = &mut (*self.extra.get()).pool_owned_threads;
+                if !pool_owned.is_empty() {
+                    pool_owned.remove(&t.1);
+                }
+                self.push_ref_at(&t.0, state)
+            }
             Value::UserData(ud) => self.push_ref_at(&ud.0, state),
             #[cfg(feature = "luau")]
             Value::Buffer(buf) => self.push_ref_at(&buf.0, state),
@@ -1063,7 +1318,7 @@ impl RawLua {
         })
     }
 
-    unsafe fn make_userdata_with_metatable<T>(
+    unsafe fn make_userdata_with_metatable<T: 'static>(
         &self,
         data: UserDataStorage<T>,
         get_metatable_id: impl FnOnce() -> Result<Integer>,
@@ -1091,7 +1346,14 @@ impl RawLua {
             ffi::lua_setuservalue(state, -2);
         }
 
-        Ok(AnyUserData(self.pop_ref()))
+        let ud = AnyUserData(self.pop_ref());
+        let extra = &*self.extra.get();
+        if let Some(instances) = &extra.userdata_instances {
+            if extra.userdata_dtor_order.contains(&TypeId::of::<T>()) {
+                instances.raw_set(&ud, true)?;
+            }
+        }
+        Ok(ud)
     }
 
     #[cfg(feature = "dynamic-userdata")]
@@ -1411,6 +1673,16 @@ impl RawLua {
 
     // Creates a Function out of a Callback containing a 'static Fn.
     pub(crate) fn create_callback(&self, func: Callback) -> Result<Function> {
+        self.create_callback_with_upvalues(func, &[])
+    }
+
+    // Creates a Function out of a Callback containing a 'static Fn, with extra upvalues
+    // (available to the callback at `lua_upvalueindex(2)` onwards)
+    pub(crate) fn create_callback_with_upvalues(
+        &self,
+        func: Callback,
+        upvalues: &[Value],
+    ) -> Result<Function> {
         unsafe extern "C-unwind" fn call_callback(state: *mut ffi::lua_State) -> c_int {
             let upvalue = get_userdata::<CallbackUpvalue>(state, ffi::lua_upvalueindex(1));
             callback_error_ext_yieldable(
@@ -1430,21 +1702,30 @@ impl RawLua {
             )
         }
 
+        // Lua limits the number of upvalues of a C closure to 255
+        if upvalues.len() > 254 {
+            return Err(Error::runtime("too many upvalues"));
+        }
+        let nupvalues = upvalues.len() as c_int + 1;
+
         let state = self.state();
         unsafe {
             let _sg = StackGuard::new(state);
-            check_stack(state, 4)?;
+            check_stack(state, nupvalues + 3)?;
 
             let func = Some(func);
             let extra = XRc::clone(&self.extra);
             let protect = !self.unlikely_memory_error();
             push_internal_userdata(state, CallbackUpvalue { data: func, extra }, protect)?;
+            for value in upvalues {
+                self.push_value_at(value, state)?;
+            }
             if protect {
-                protect_lua!(state, 1, 1, fn(state) {
-                    ffi::lua_pushcclosure(state, call_callback, 1);
+                protect_lua!(state, nupvalues, 1, |state| {
+                    ffi::lua_pushcclosure(state, call_callback, nupvalues);
                 })?;
             } else {
-                ffi::lua_pushcclosure(state, call_callback, 1);
+                ffi::lua_pushcclosure(state, call_callback, nupvalues);
             }
 
             Ok(Function(self.pop_ref()))
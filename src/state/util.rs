@@ -1,4 +1,5 @@
 use crate::IntoLuaMulti;
+use std::any::Any;
 use std::ffi::c_char;
 use std::mem::take;
 use std::os::raw::c_int;
@@ -159,7 +160,7 @@ where
 
             let wrapped_error = prealloc_failure.r#use(state, extra);
 
-            if !wrap_error {
+            if !wrap_error || is_reraised_error(extra, &err) {
                 ptr::write(wrapped_error, WrappedFailure::Error(err));
                 get_internal_metatable::<WrappedFailure>(state);
                 ffi::lua_setmetatable(state, -2);
@@ -186,6 +187,7 @@ where
             ffi::lua_error(state)
         }
         Err(p) => {
+            call_panic_handler(extra, &*p);
             if (*extra).disable_error_userdata {
                 {
                     // Push the error message directly onto the stack
@@ -217,6 +219,24 @@ where
     }
 }
 
+// Checks whether `err` is the callback error returned by `Lua::raise`, which must be passed
+// through as is to keep the original traceback
+unsafe fn is_reraised_error(extra: *mut ExtraData, err: &Error) -> bool {
+    let reraised = (*extra).reraised_error.take().and_then(|cause| cause.upgrade());
+    match (err, reraised) {
+        (Error::CallbackError { cause, .. }, Some(reraised)) => Arc::ptr_eq(cause, &reraised),
+        _ => false,
+    }
+}
+
+// Invokes the panic handler (if set) before the panic is converted to a `WrappedFailure`
+unsafe fn call_panic_handler(extra: *mut ExtraData, payload: &(dyn Any + Send)) {
+    if let Some(panic_handler) = (*extra).panic_handler.clone() {
+        // Panics inside the handler are ignored
+        let _ = catch_unwind(AssertUnwindSafe(|| panic_handler((*extra).lua(), payload)));
+    }
+}
+
 /// An yieldable version of `callback_error_ext`
 ///
 /// Unlike ``callback_error_ext``, this method requires a c_int return
@@ -383,7 +403,7 @@ where
 
             let wrapped_error = prealloc_failure.r#use(state, extra);
 
-            if !wrap_error {
+            if !wrap_error || is_reraised_error(extra, &err) {
                 ptr::write(wrapped_error, WrappedFailure::Error(err));
                 get_internal_metatable::<WrappedFailure>(state);
                 ffi::lua_setmetatable(state, -2);
@@ -410,6 +430,7 @@ where
             ffi::lua_error(state)
         }
         Err(p) => {
+            call_panic_handler(extra, &*p);
             if (*extra).disable_error_userdata {
                 // Push the error message directly onto the stack
                 let err_msg = {
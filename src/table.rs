@@ -1,4 +1,5 @@
 use std::collections::HashSet;
+use std::ffi::CStr;
 use std::fmt;
 use std::marker::PhantomData;
 use std::os::raw::c_void;
@@ -335,7 +336,52 @@ impl Table {
         Ok(())
     }
 
+    /// Appends all values from the iterator to the back of the table, without invoking
+    /// metamethods.
+    ///
+    /// The values are stored starting at index `raw_len() + 1`.
+    /// This is a faster alternative to calling [`Table::raw_push`] for every element, as the values
+    /// are collected into storage sized up front using the iterator's size hint.
+    pub fn extend<T, I>(&self, iter: I) -> Result<()>
+    where
+        T: IntoLua,
+        I: IntoIterator<Item = T>,
+    {
+        let lua = self.0.lua.lock();
+        let state = lua.state();
+        unsafe {
+            #[cfg(feature = "luau")]
+            self.check_readonly_write(&lua)?;
+
+            let _sg = StackGuard::new(state);
+            check_stack(state, 5)?;
+
+            // Stage the values in a sequence sized up front from the iterator's size hint, then
+            // move them to the back of this table at once
+            let items = lua.create_sequence_from(iter)?;
+            lua.push_ref_at(&self.0, state);
+            lua.push_ref_at(&items.0, state);
+            let n = ffi::lua_rawlen(state, -1) as Integer;
+            let append = |state: *mut ffi::lua_State| {
+                let len = ffi::lua_rawlen(state, -2) as Integer;
+                for i in 1..=n {
+                    ffi::lua_rawgeti(state, -1, i);
+                    ffi::lua_rawseti(state, -3, len + i);
+                }
+            };
+            if lua.unlikely_memory_error() {
+                append(state);
+            } else {
+                protect_lua!(state, 2, 0, append)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Removes the last element from the table and returns it, without invoking metamethods.
+    ///
+    /// Together with [`Table::raw_push`] this allows to use a sequence table as a stack.
+    /// If the table is empty, `nil` is returned and the table is left unchanged.
     pub fn raw_pop<V: FromLua>(&self) -> Result<V> {
         let lua = self.0.lua.lock();
         let state = lua.state();
@@ -409,6 +455,7 @@ impl Table {
             #[cfg(not(feature = "luau"))]
             {
                 let state = lua.state();
+                let _sg = StackGuard::new(state);
                 check_stack(state, 4)?;
 
                 lua.push_ref_at(&self.0, state);
@@ -491,7 +538,9 @@ impl Table {
     ///
     /// If `metatable` is `None`, the metatable is removed (if no metatable is set, this does
     /// nothing).
-    pub fn set_metatable(&self, metatable: Option<Table>) -> Result<()> {
+    ///
+    /// Returns the previous metatable (if any), so it can be restored later.
+    pub fn set_metatable(&self, metatable: Option<Table>) -> Result<Option<Table>> {
         // Workaround to throw readonly error without returning Result
         #[cfg(feature = "luau")]
         if self.is_readonly() {
@@ -505,15 +554,18 @@ impl Table {
             assert_stack(state, 2);
 
             lua.push_ref_at(&self.0, state);
+            let prev = match ffi::lua_getmetatable(state, -1) {
+                0 => None,
+                _ => Some(Table(lua.pop_ref())),
+            };
             if let Some(metatable) = &metatable {
                 lua.push_ref_at(&metatable.0, state);
             } else {
                 ffi::lua_pushnil(state);
             }
             ffi::lua_setmetatable(state, -2);
+            Ok(prev)
         }
-
-        Ok(())
     }
 
     /// Returns true if the table has metatable attached.
@@ -648,6 +700,88 @@ impl Table {
         Ok(())
     }
 
+    /// Collects all keys of the table into a vector.
+    ///
+    /// The order of keys is unspecified. This method does not invoke metamethods.
+    pub fn keys(&self) -> Result<Vec<Value>> {
+        let mut keys = Vec::new();
+        self.for_each::<Value, Value>(|key, _| {
+            keys.push(key);
+            Ok(())
+        })?;
+        Ok(keys)
+    }
+
+    /// Returns keys of this table that are not present in the `other` table.
+    ///
+    /// This is useful for comparing two versions of a table, eg. calling `old.difference_keys(&new)`
+    /// gives the removed keys and `new.difference_keys(&old)` the added ones.
+    ///
+    /// The order of keys is unspecified. This method does not invoke metamethods.
+    pub fn difference_keys(&self, other: &Table) -> Result<Vec<Value>> {
+        let mut keys = Vec::new();
+        self.for_each::<Value, Value>(|key, _| {
+            if other.raw_get::<Value>(&key)?.is_nil() {
+                keys.push(key);
+            }
+            Ok(())
+        })?;
+        Ok(keys)
+    }
+
+    /// Creates a new table with the same keys as this table and values transformed by `f`.
+    ///
+    /// The traversal does not invoke metamethods and holds the Lua lock once for the whole
+    /// iteration. The array part of the new table is presized using the raw length of this table.
+    /// Returning `nil` from `f` omits the key from the new table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mluau::{Lua, Result, Value};
+    /// # fn main() -> Result<()> {
+    /// # let lua = Lua::new();
+    /// let t = lua.create_sequence_from([1, 2, 3])?;
+    /// let doubled = t.map_values(|v| Ok(Value::Integer(v.as_integer().unwrap_or_default() * 2)))?;
+    /// assert_eq!(doubled.get::<i64>(3)?, 6);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn map_values<F>(&self, mut f: F) -> Result<Table>
+    where
+        F: FnMut(Value) -> Result<Value>,
+    {
+        let lua = self.0.lua.lock();
+        let state = lua.state();
+        unsafe {
+            let _sg = StackGuard::new(state);
+            check_stack(state, 6)?;
+
+            let narr = ffi::lua_rawlen(lua.ref_thread(self.0.aux_thread), self.0.index);
+            let table = lua.create_table_with_capacity(narr, 0)?;
+            lua.push_ref_at(&table.0, state);
+            let table_idx = ffi::lua_gettop(state);
+
+            lua.push_ref_at(&self.0, state);
+            ffi::lua_pushnil(state);
+            while ffi::lua_next(state, -2) != 0 {
+                let value = f(lua.pop_value_at(state)?)?;
+                // Keep key for next iteration
+                ffi::lua_pushvalue(state, table_idx);
+                ffi::lua_pushvalue(state, -2);
+                lua.push_value_at(&value, state)?;
+                if lua.unlikely_memory_error() {
+                    ffi::lua_rawset(state, -3);
+                    ffi::lua_pop(state, 1);
+                } else {
+                    protect_lua!(state, 3, 0, fn(state) ffi::lua_rawset(state, -3))?;
+                }
+            }
+
+            Ok(table)
+        }
+    }
+
     /// Returns an iterator over all values in the sequence part of the table.
     ///
     /// The iterator will yield all values `t[1]`, `t[2]` and so on, until a `nil` value is
@@ -694,6 +828,50 @@ impl Table {
         self.for_each_value_by_len(None, f)
     }
 
+    /// Returns a summary of the table's structure, computed in a single raw traversal.
+    ///
+    /// Entries with integer keys in `1..=raw_len()` are counted as the array part, all other
+    /// entries as the hash part. This method does not invoke metamethods.
+    pub fn describe(&self) -> TableShape {
+        let lua = self.0.lua.lock();
+        let state = lua.state();
+        let mut shape = TableShape::default();
+        unsafe {
+            let _sg = StackGuard::new(state);
+            assert_stack(state, 4);
+
+            lua.push_ref_at(&self.0, state);
+            let len = ffi::lua_rawlen(state, -1);
+            shape.has_metatable = ffi::lua_getmetatable(state, -1) != 0;
+            if shape.has_metatable {
+                ffi::lua_pop(state, 1);
+            }
+
+            ffi::lua_pushnil(state);
+            while ffi::lua_next(state, -2) != 0 {
+                let key_type = ffi::lua_type(state, -2);
+                let in_array = key_type == ffi::LUA_TNUMBER && {
+                    let n = ffi::lua_tonumber(state, -2);
+                    (1.0..=len as ffi::lua_Number).contains(&n) && n.fract() == 0.0
+                };
+                if in_array {
+                    shape.array_len += 1;
+                } else {
+                    shape.hash_len += 1;
+                }
+                // `lua_typename` returns a static string
+                let type_name = CStr::from_ptr(ffi::lua_typename(state, key_type));
+                let type_name = type_name.to_str().unwrap_or("unknown");
+                if !shape.key_types.contains(&type_name) {
+                    shape.key_types.push(type_name);
+                }
+                ffi::lua_pop(state, 1);
+            }
+        }
+        shape.key_types.sort_unstable();
+        shape
+    }
+
     fn for_each_value_by_len<V: FromLua>(
         &self,
         len: impl Into<Option<usize>>,
@@ -1294,3 +1472,18 @@ mod assertions {
     #[cfg(feature = "send")]
     static_assertions::assert_impl_all!(Table: Send, Sync);
 }
+
+/// Structural summary of a Lua table.
+///
+/// This struct is created by the [`Table::describe`] method.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TableShape {
+    /// Number of entries with integer keys in `1..=raw_len()`.
+    pub array_len: usize,
+    /// Number of all other entries.
+    pub hash_len: usize,
+    /// Sorted names of the Lua types of the keys present in the table.
+    pub key_types: Vec<&'static str>,
+    /// Whether the table has a metatable.
+    pub has_metatable: bool,
+}
@@ -1,17 +1,19 @@
 use std::fmt;
-use std::os::raw::{c_int, c_void};
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
 use std::string::String as StdString;
 
 use crate::error::{Error, Result};
 use crate::function::Function;
 use crate::state::RawLua;
 use crate::traits::{FromLuaMulti, IntoLuaMulti};
-use crate::types::{LuaType, ValueRef};
+use crate::types::{LuaType, MaybeSend, ThreadErrorHandler, ValueRef, XRc};
 #[cfg(feature = "luau")]
-use crate::types::{MaybeSync, XRc};
-use crate::util::{check_stack, error_traceback_thread, pop_error, StackGuard};
-#[cfg(feature = "luau")]
-use crate::MaybeSend;
+use crate::types::MaybeSync;
+use crate::util::{
+    check_stack, error_traceback_thread, get_internal_userdata, pop_error, push_internal_userdata, StackGuard,
+};
+use crate::Lua;
 
 use crate::WeakLua;
 #[cfg(not(feature = "luau"))]
@@ -20,6 +22,9 @@ use crate::{
     types::HookKind,
 };
 
+// Key to store thread error handlers in the registry
+const ERROR_HANDLERS_KEY: *const c_char = cstr!("__mlua_thread_error_handlers");
+
 /// Continuation thread status. Can either be Ok, Yielded (rare, but can happen) or Error
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum ContinuationStatus {
@@ -308,6 +313,61 @@ impl Thread {
         }
     }
 
+    /// Resumes execution of this thread, passing the values yielded by `source` as arguments.
+    ///
+    /// The values are moved directly from the `source` thread stack without converting them to
+    /// Rust values. The `source` thread must be suspended in a yield.
+    ///
+    /// Unlike [`Thread::resume`], the results of this thread are left on its stack, so they can
+    /// be passed further by calling this method on another thread with this one as `source`,
+    /// or retrieved using [`Thread::pop_results`].
+    ///
+    /// Returns the new thread status and the number of values left on the thread stack.
+    pub fn resume_from_yield(&self, source: &Thread) -> Result<(ThreadStatus, c_int)> {
+        let lua = self.0.lua.lock();
+        if source.0.lua != self.0.lua {
+            return Err(Error::runtime(
+                "source thread belongs to a different Lua instance",
+            ));
+        }
+        let pushed_nargs = match self.status_inner(&lua) {
+            ThreadStatusInner::New(nargs) | ThreadStatusInner::Yielded(nargs) => nargs,
+            _ => return Err(Error::CoroutineUnresumable),
+        };
+
+        let thread_state = self.state();
+        let source_state = source.state();
+        if source_state == thread_state
+            || source_state == lua.state()
+            || unsafe { ffi::lua_status(source_state) } != ffi::LUA_YIELD
+        {
+            return Err(Error::runtime("source thread is not suspended in a yield"));
+        }
+
+        unsafe {
+            let _sg = StackGuard::new(lua.state());
+
+            let nargs = ffi::lua_gettop(source_state);
+            if nargs > 0 {
+                check_stack(thread_state, nargs)?;
+                ffi::lua_xmove(source_state, thread_state, nargs);
+            }
+
+            let (status, nresults) = match self.resume_inner(&lua, pushed_nargs + nargs) {
+                Ok(res) => res,
+                Err(err) => {
+                    ffi::lua_settop(thread_state, 0);
+                    return Err(err);
+                }
+            };
+            let status = match status {
+                ThreadStatusInner::Finished => ThreadStatus::Finished,
+                _ => ThreadStatus::Resumable,
+            };
+            Ok((status, nresults))
+        }
+    }
+
     /// Resumes execution of this thread.
     ///
     /// It's similar to `resume()` but leaves `nresults` values on the thread stack.
@@ -325,16 +385,79 @@ impl Thread {
             ffi::LUA_YIELD => Ok((ThreadStatusInner::Yielded(0), nresults)),
             ffi::LUA_ERRMEM => {
                 // Don't call error handler for memory errors
-                Err(pop_error(thread_state, ret))
+                let err = pop_error(thread_state, ret);
+                Err(self.apply_error_handler(lua, err))
             }
             _ => {
                 check_stack(state, 3)?;
                 protect_lua!(state, 0, 1, |state| error_traceback_thread(state, thread_state))?;
-                Err(pop_error(state, ret))
+                let err = pop_error(state, ret);
+                Err(self.apply_error_handler(lua, err))
             }
         }
     }
 
+    /// Sets a function to transform errors raised by this thread.
+    ///
+    /// The handler is called with every error that terminates the thread (after the traceback is
+    /// attached), before it is returned from [`Thread::resume`] and similar methods. It can be used
+    /// by schedulers to tag errors originating from a specific coroutine, e.g. with a task id.
+    ///
+    /// Setting a new handler replaces the previous one.
+    pub fn set_error_handler<F>(&self, handler: F) -> Result<()>
+    where
+        F: Fn(&Lua, Error) -> Error + MaybeSend + 'static,
+    {
+        let lua = self.0.lua.lock();
+        let state = lua.state();
+        let handler: ThreadErrorHandler = XRc::new(handler);
+        unsafe {
+            let _sg = StackGuard::new(state);
+            check_stack(state, 4)?;
+
+            // Handlers are stored in the registry (in a weak table keyed by thread)
+            protect_lua!(state, 0, 0, |state| {
+                if ffi::luaL_getsubtable(state, ffi::LUA_REGISTRYINDEX, ERROR_HANDLERS_KEY) == 0 {
+                    // Table just created, initialize it
+                    ffi::lua_pushliteral(state, c"k");
+                    ffi::lua_setfield(state, -2, cstr!("__mode")); // handlers.__mode = "k"
+                    ffi::lua_pushvalue(state, -1);
+                    ffi::lua_setmetatable(state, -2); // metatable(handlers) = handlers
+                }
+
+                lua.push_ref_at(&self.0, state); // key (thread)
+                let _ = push_internal_userdata(state, handler, false); // value (error handler)
+                ffi::lua_rawset(state, -3); // handlers[thread] = error handler
+            })
+        }
+    }
+
+    /// Passes `err` through the error handler of this thread, if set.
+    unsafe fn apply_error_handler(&self, lua: &RawLua, err: Error) -> Error {
+        let state = lua.state();
+        let handler = {
+            let _sg = StackGuard::new(state);
+            if ffi::lua_checkstack(state, 2) == 0 {
+                return err;
+            }
+            let mut handler = None;
+            if ffi::lua_getfield(state, ffi::LUA_REGISTRYINDEX, ERROR_HANDLERS_KEY) == ffi::LUA_TTABLE {
+                lua.push_ref_at(&self.0, state);
+                if ffi::lua_rawget(state, -2) == ffi::LUA_TUSERDATA {
+                    let handler_ptr = get_internal_userdata::<ThreadErrorHandler>(state, -1, ptr::null());
+                    if !handler_ptr.is_null() {
+                        handler = Some((*handler_ptr).clone());
+                    }
+                }
+            }
+            handler
+        };
+        match handler {
+            Some(handler) => handler(lua.lua(), err),
+            None => err,
+        }
+    }
+
     /// Gets the status of the thread.
     pub fn status(&self) -> ThreadStatus {
         match self.status_inner(&self.0.lua.lock()) {
@@ -474,6 +597,68 @@ impl Thread {
         }
     }
 
+    /// Resets the thread and releases its reference slot for reuse by the thread pool.
+    ///
+    /// Returns `None` if the thread cannot be reused (eg. it has other handles, was passed to Lua
+    /// or cannot be reset).
+    pub(crate) fn into_pool_slot(mut self, lua: &RawLua) -> Option<(usize, c_int)> {
+        let thread_state = self.state();
+        if thread_state == lua.main_state() {
+            return None;
+        }
+        let Some(index) = &self.0.index_count else {
+            return None;
+        };
+        // Only threads acquired from the pool through this very handle are uniquely owned
+        let pool_owned = unsafe { &mut (*lua.extra()).pool_owned_threads };
+        let owned = pool_owned
+            .get(&thread_state)
+            .is_some_and(|owner| owner.as_ptr() == XRc::as_ptr(&index.0));
+        if !owned || XRc::strong_count(&index.0) != 1 {
+            return None;
+        }
+        pool_owned.remove(&thread_state);
+        unsafe {
+            let status = self.status_inner(lua);
+            self.reset_inner(status).ok()?;
+            ffi::lua_settop(thread_state, 0);
+            self.clear_thread_state(lua);
+        }
+        // Prevent the slot from being freed when the handle is dropped
+        self.0.index_count = None;
+        Some((self.0.aux_thread, self.0.index))
+    }
+
+    // Drops the per-thread state (hook, error handler, thread data) so it doesn't leak into the
+    // next owner
+    unsafe fn clear_thread_state(&self, lua: &RawLua) {
+        let thread_state = self.state();
+
+        let state = lua.state();
+        {
+            let _sg = StackGuard::new(state);
+            if ffi::lua_checkstack(state, 3) != 0
+                && ffi::lua_getfield(state, ffi::LUA_REGISTRYINDEX, ERROR_HANDLERS_KEY) == ffi::LUA_TTABLE
+            {
+                lua.push_ref_at(&self.0, state); // key (thread)
+                ffi::lua_pushnil(state);
+                ffi::lua_rawset(state, -3); // handlers[thread] = nil
+            }
+        }
+
+        #[cfg(not(feature = "luau"))]
+        lua.remove_thread_hook(thread_state);
+
+        #[cfg(feature = "luau")]
+        {
+            let data = ffi::lua_getthreaddata(thread_state);
+            if !data.is_null() {
+                ffi::lua_setthreaddata(thread_state, ptr::null_mut());
+                drop(Box::from_raw(data as *mut crate::types::ThreadData));
+            }
+        }
+    }
+
     /// Closes a thread and marks it as finished.
     ///
     /// In [Lua 5.4]: cleans its call stack and closes all pending to-be-closed variables.
@@ -556,6 +741,35 @@ impl Thread {
         }
     }
 
+    /// Replaces the global environment of the thread with the provided table.
+    ///
+    /// Unlike [`Thread::sandbox`], which creates a proxy table inheriting the main globals, this
+    /// allows to give each thread a specifically prepared environment. Any code loaded and run
+    /// within the thread will see `env` as its globals.
+    ///
+    /// The `safeenv` flag of `env` is set to match its readonly state (as [`Lua::sandbox`] does),
+    /// so a mutable environment does not get import optimizations that would ignore later
+    /// modifications.
+    ///
+    /// Resetting the thread with [`Thread::reset`] restores the main globals.
+    ///
+    /// Requires `feature = "luau"`
+    #[cfg(any(feature = "luau", doc))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "luau")))]
+    pub fn set_environment(&self, env: crate::Table) -> Result<()> {
+        let lua = self.0.lua.lock();
+        let thread_state = self.state();
+        unsafe {
+            check_stack(thread_state, 1)?;
+
+            lua.push_ref_at(&env.0, thread_state);
+            let readonly = ffi::lua_getreadonly(thread_state, -1);
+            ffi::lua_setsafeenv(thread_state, -1, readonly);
+            ffi::lua_replace(thread_state, ffi::LUA_GLOBALSINDEX);
+        }
+        Ok(())
+    }
+
     /// Converts this thread to a generic C pointer.
     ///
     /// There is no way to convert the pointer back to its original value.
@@ -100,6 +100,16 @@ pub trait IntoLuaMulti: Sized {
         }
         Ok(len)
     }
+
+    /// Returns the number of values this type always converts into, if it is known in advance.
+    ///
+    /// Returns `None` when the number of values depends on the value itself (eg. [`MultiValue`]).
+    /// This hint is used to validate callbacks that must return a fixed number of values, such as
+    /// the `__len` metamethod.
+    #[inline]
+    fn expected_len() -> Option<usize> {
+        None
+    }
 }
 
 /// Trait for types that can be created from an arbitrary number of Lua values.
@@ -123,8 +133,7 @@ pub trait FromLuaMulti: Sized {
     #[doc(hidden)]
     #[inline]
     fn from_lua_args(args: MultiValue, i: usize, to: Option<&str>, lua: &Lua) -> Result<Self> {
-        let _ = (i, to);
-        Self::from_lua_multi(args, lua)
+        Self::from_lua_multi(args, lua).map_err(|err| bad_argument(err, i, to))
     }
 
     /// Performs the conversion for a number of values in the specified Lua stack.
@@ -152,7 +161,6 @@ pub trait FromLuaMulti: Sized {
         lua: &RawLua,
         state: *mut ffi::lua_State,
     ) -> Result<Self> {
-        let _ = (i, to);
         Self::from_specified_stack_multi(nvals, lua, state).map_err(|err| Error::BadArgument {
             to: to.map(|s| s.to_string()),
             pos: i,
@@ -162,6 +170,27 @@ pub trait FromLuaMulti: Sized {
     }
 }
 
+// Wraps a conversion failure of the `MultiValue` arguments starting at position `i` into
+// `Error::BadArgument`.
+//
+// Errors that are already positioned (eg. reported by a nested argument) or not caused by the
+// argument values (eg. memory errors) are passed through unchanged.
+fn bad_argument(err: Error, i: usize, to: Option<&str>) -> Error {
+    match err {
+        Error::FromLuaConversionError { .. }
+        | Error::UserDataTypeMismatch
+        | Error::UserDataDestructed
+        | Error::UserDataBorrowError
+        | Error::UserDataBorrowMutError => Error::BadArgument {
+            to: to.map(|s| s.to_string()),
+            pos: i,
+            name: None,
+            cause: Arc::new(err),
+        },
+        err => err,
+    }
+}
+
 /// A trait for types that can be used as Lua objects (usually table and userdata).
 pub trait ObjectLike: Sealed {
     /// Gets the value associated to `key` from the object, assuming it has `__index` metamethod.
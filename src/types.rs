@@ -3,7 +3,7 @@ use std::os::raw::{c_int, c_void};
 
 #[cfg(not(feature = "luau"))]
 use crate::debug::{Debug, HookTriggers};
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::state::{ExtraData, Lua, RawLua};
 
 // Re-export mutex wrappers
@@ -12,7 +12,7 @@ pub(crate) use sync::{ArcReentrantMutexGuard, ReentrantMutex, ReentrantMutexGuar
 
 pub use app_data::{AppData, AppDataRef, AppDataRefMut};
 pub use either::Either;
-pub use registry_key::RegistryKey;
+pub use registry_key::{LightRef, RegistryKey};
 pub(crate) use value_ref::ValueRef;
 
 #[cfg(feature = "luau")]
@@ -97,6 +97,15 @@ pub enum VmState {
 pub(crate) enum HookKind {
     Global,
     Thread(HookTriggers, HookCallback),
+    // Re-applies the hook installed on the thread (eg. after the call depth limit changed)
+    Refresh,
+}
+
+// Hook set for a specific thread, stored in the registry
+#[cfg(not(feature = "luau"))]
+pub(crate) struct ThreadHook {
+    pub(crate) triggers: HookTriggers,
+    pub(crate) callback: HookCallback,
 }
 
 #[cfg(all(feature = "send", not(feature = "luau")))]
@@ -105,6 +114,12 @@ pub(crate) type HookCallback = XRc<dyn Fn(&Lua, &Debug) -> Result<VmState> + Sen
 #[cfg(all(not(feature = "send"), not(feature = "luau")))]
 pub(crate) type HookCallback = XRc<dyn Fn(&Lua, &Debug) -> Result<VmState>>;
 
+#[cfg(feature = "send")]
+pub(crate) type ThreadErrorHandler = XRc<dyn Fn(&Lua, Error) -> Error + Send>;
+
+#[cfg(not(feature = "send"))]
+pub(crate) type ThreadErrorHandler = XRc<dyn Fn(&Lua, Error) -> Error>;
+
 #[cfg(all(feature = "send", feature = "luau"))]
 pub(crate) type InterruptCallback = XRc<dyn Fn(&Lua) -> Result<VmState> + Send>;
 
@@ -132,6 +147,12 @@ pub(crate) type WarnCallback = XRc<dyn Fn(&Lua, &str, bool) -> Result<()> + Send
 #[cfg(all(not(feature = "send"), feature = "lua54"))]
 pub(crate) type WarnCallback = XRc<dyn Fn(&Lua, &str, bool) -> Result<()>>;
 
+#[cfg(feature = "send")]
+pub(crate) type PanicHandler = XRc<dyn Fn(&Lua, &(dyn std::any::Any + Send)) + Send>;
+
+#[cfg(not(feature = "send"))]
+pub(crate) type PanicHandler = XRc<dyn Fn(&Lua, &(dyn std::any::Any + Send))>;
+
 /// A trait that adds `Send` requirement if `send` feature is enabled.
 #[cfg(feature = "send")]
 pub trait MaybeSend: Send {}
@@ -158,6 +179,46 @@ impl<T> MaybeSync for T {}
 
 pub(crate) struct DestructedUserdata;
 
+/// A value that can be accessed (and dropped) only on the thread that created it.
+///
+/// It's `Send` and `Sync` regardless of `T`, as access from other threads is checked at runtime.
+/// If dropped on another thread, the inner value is leaked.
+pub(crate) struct ThreadConfined<T> {
+    value: std::mem::ManuallyDrop<T>,
+    thread: std::thread::ThreadId,
+}
+
+#[cfg(feature = "send")]
+unsafe impl<T> Send for ThreadConfined<T> {}
+#[cfg(feature = "send")]
+unsafe impl<T> Sync for ThreadConfined<T> {}
+
+impl<T> ThreadConfined<T> {
+    pub(crate) fn new(value: T) -> Self {
+        ThreadConfined {
+            value: std::mem::ManuallyDrop::new(value),
+            thread: std::thread::current().id(),
+        }
+    }
+
+    pub(crate) fn get(&self) -> Result<&T> {
+        if std::thread::current().id() != self.thread {
+            return Err(crate::Error::runtime(
+                "thread-confined value accessed from a different thread",
+            ));
+        }
+        Ok(&self.value)
+    }
+}
+
+impl<T> Drop for ThreadConfined<T> {
+    fn drop(&mut self) {
+        if std::thread::current().id() == self.thread {
+            unsafe { std::mem::ManuallyDrop::drop(&mut self.value) };
+        }
+    }
+}
+
 pub(crate) trait LuaType {
     const TYPE_ID: c_int;
 }
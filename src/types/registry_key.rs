@@ -92,6 +92,26 @@ impl RegistryKey {
     }
 }
 
+/// A lightweight integer reference into the Lua registry.
+///
+/// Created by [`Lua::create_light_ref`], this is a plain `luaL_ref` slot without any bookkeeping.
+/// Unlike [`RegistryKey`] it has no `Drop` implementation: the slot stays occupied until the
+/// reference is explicitly released with [`Lua::drop_light_ref`]. It cannot be copied, so a slot
+/// is released at most once.
+///
+/// [`Lua::create_light_ref`]: crate::Lua::create_light_ref
+/// [`Lua::drop_light_ref`]: crate::Lua::drop_light_ref
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct LightRef(pub(crate) c_int);
+
+impl LightRef {
+    /// Returns the underlying Lua reference of this `LightRef`
+    #[inline(always)]
+    pub fn id(&self) -> c_int {
+        self.0
+    }
+}
+
 #[cfg(test)]
 mod assertions {
     use super::*;
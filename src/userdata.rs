@@ -32,7 +32,7 @@ pub(crate) use registry::{RawUserDataRegistry, UserDataProxy};
 pub(crate) use util::collect_userdata_dyn;
 pub(crate) use util::{
     borrow_userdata_scoped, borrow_userdata_scoped_mut, collect_userdata, init_userdata_metatable,
-    TypeIdHints,
+    track_userdata_borrow, userdata_borrow_error, TypeIdHints,
 };
 
 /// Kinds of metamethods that can be overridden.
@@ -225,6 +225,16 @@ impl MetaMethod {
         }
     }
 
+    /// Returns the number of values the metamethod `name` must return, if it is fixed.
+    pub(crate) fn expected_results(name: &str) -> Option<usize> {
+        match name {
+            "__add" | "__sub" | "__mul" | "__div" | "__mod" | "__pow" | "__unm" | "__idiv" | "__band"
+            | "__bor" | "__bxor" | "__bnot" | "__shl" | "__shr" | "__concat" | "__len" | "__eq" | "__lt"
+            | "__le" | "__tostring" => Some(1),
+            _ => None,
+        }
+    }
+
     pub(crate) fn validate(name: &str) -> Result<&str> {
         match name {
             // __gc is safe on Luau as it doesnt actually exist
@@ -481,6 +491,25 @@ pub trait UserDataFields<T> {
         M: FnMut(&Lua, &mut T, A) -> Result<()> + MaybeSend + 'static,
         A: FromLua;
 
+    /// Add a regular field with both a getter and a setter method.
+    ///
+    /// This is a shorthand for calling [`add_field_method_get`] and [`add_field_method_set`] with
+    /// the same field name.
+    ///
+    /// [`add_field_method_get`]: UserDataFields::add_field_method_get
+    /// [`add_field_method_set`]: UserDataFields::add_field_method_set
+    fn add_field_method<G, S, R, A>(&mut self, name: impl Into<StdString>, get: G, set: S)
+    where
+        G: Fn(&Lua, &T) -> Result<R> + MaybeSend + 'static,
+        S: FnMut(&Lua, &mut T, A) -> Result<()> + MaybeSend + 'static,
+        R: IntoLua,
+        A: FromLua,
+    {
+        let name = name.into();
+        self.add_field_method_get(name.clone(), get);
+        self.add_field_method_set(name, set);
+    }
+
     /// Add a regular field getter as a function which accepts a generic [`AnyUserData`] of type `T`
     /// argument.
     fn add_field_function_get<F, R>(&mut self, name: impl Into<StdString>, function: F)
@@ -633,6 +662,43 @@ impl AnyUserData {
         matches!(type_id, Some(type_id) if type_id == TypeId::of::<T>())
     }
 
+    /// Starts dispatching on the concrete type of this userdata.
+    ///
+    /// The type of the userdata is looked up once; each [`UserDataDispatch::on`] (or
+    /// [`UserDataDispatch::on_mut`]) arm is then tested against it, and the first matching arm is
+    /// invoked with the borrowed value.
+    ///
+    /// Like [`AnyUserData::is`], only the exact type `T` is matched (and never dynamic userdata).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mluau::{AnyUserData, Lua, Result, UserData};
+    /// # fn main() -> Result<()> {
+    /// # let lua = Lua::new();
+    /// struct Circle(f64);
+    /// struct Square(f64);
+    /// impl UserData for Circle {}
+    /// impl UserData for Square {}
+    ///
+    /// let area = |ud: &AnyUserData| {
+    ///     ud.dispatch()
+    ///         .on(|c: &Circle| Ok(3.14 * c.0 * c.0))
+    ///         .on(|s: &Square| Ok(s.0 * s.0))
+    ///         .finish()
+    /// };
+    /// assert_eq!(area(&lua.create_userdata(Square(2.0))?)?, 4.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dispatch<R>(&self) -> UserDataDispatch<'_, R> {
+        UserDataDispatch {
+            ud: self,
+            type_id: self.type_id(),
+            result: None,
+        }
+    }
+
     /// Borrow this userdata immutably if it is of type `T`.
     ///
     /// # Errors
@@ -1064,6 +1130,30 @@ impl AnyUserData {
         }
     }
 
+    /// Returns a human readable description of this userdata, suitable for logging.
+    ///
+    /// The description contains the type name (or `userdata` if not set) and the pointer of this
+    /// userdata. If the `__tostring` metamethod is defined, its result is appended as well.
+    ///
+    /// Unlike [`AnyUserData::borrow`], this method does not need to know the concrete Rust type and
+    /// never fails: any error raised while describing the userdata is silently ignored.
+    pub fn debug_string(&self) -> StdString {
+        let name = self.type_name().ok().flatten();
+        let name = name.as_deref().unwrap_or("userdata");
+        let mut desc = format!("{name}: {:?}", self.to_pointer());
+
+        let has_tostring = self
+            .raw_metatable()
+            .and_then(|mt| mt.contains_key(MetaMethod::ToString.name()))
+            .unwrap_or(false);
+        if has_tostring {
+            if let Ok(s) = Value::UserData(self.clone()).to_string() {
+                desc.push_str(&format!(" ({s})"));
+            }
+        }
+        desc
+    }
+
     pub(crate) fn equals(&self, other: &Self) -> Result<bool> {
         // Uses lua_rawequal() under the hood
         if self == other {
@@ -1097,6 +1187,64 @@ impl AnyUserData {
     }
 }
 
+/// Type-based dispatcher for [`AnyUserData`].
+///
+/// This struct is created by the [`AnyUserData::dispatch`] method.
+#[must_use = "call `finish` or `otherwise` to get the result"]
+pub struct UserDataDispatch<'a, R> {
+    ud: &'a AnyUserData,
+    type_id: Option<TypeId>,
+    result: Option<Result<R>>,
+}
+
+impl<R> UserDataDispatch<'_, R> {
+    /// Calls `f` with an immutable borrow of the userdata if it's of type `T` and no previous arm
+    /// has matched.
+    pub fn on<T: 'static>(mut self, f: impl FnOnce(&T) -> Result<R>) -> Self {
+        if self.result.is_none() && self.type_id == Some(TypeId::of::<T>()) {
+            let lua = self.ud.0.lua.lock();
+            let type_hints = TypeIdHints::new::<T>();
+            let res = unsafe {
+                let ref_thread = lua.ref_thread(self.ud.0.aux_thread);
+                borrow_userdata_scoped(ref_thread, self.ud.0.index, self.type_id, type_hints, f)
+            };
+            self.result = Some(res.and_then(|r| r));
+        }
+        self
+    }
+
+    /// Calls `f` with a mutable borrow of the userdata if it's of type `T` and no previous arm
+    /// has matched.
+    pub fn on_mut<T: 'static>(mut self, f: impl FnOnce(&mut T) -> Result<R>) -> Self {
+        if self.result.is_none() && self.type_id == Some(TypeId::of::<T>()) {
+            let lua = self.ud.0.lua.lock();
+            let type_hints = TypeIdHints::new::<T>();
+            let res = unsafe {
+                let ref_thread = lua.ref_thread(self.ud.0.aux_thread);
+                borrow_userdata_scoped_mut(ref_thread, self.ud.0.index, self.type_id, type_hints, f)
+            };
+            self.result = Some(res.and_then(|r| r));
+        }
+        self
+    }
+
+    /// Returns the result of the matched arm.
+    ///
+    /// Returns [`Error::UserDataTypeMismatch`] if no arm has matched.
+    pub fn finish(self) -> Result<R> {
+        self.result.unwrap_or(Err(Error::UserDataTypeMismatch))
+    }
+
+    /// Returns the result of the matched arm, or calls `f` with the userdata if no arm has
+    /// matched.
+    pub fn otherwise(self, f: impl FnOnce(&AnyUserData) -> Result<R>) -> Result<R> {
+        match self.result {
+            Some(res) => res,
+            None => f(self.ud),
+        }
+    }
+}
+
 /// Handle to a [`AnyUserData`] metatable.
 #[derive(Clone, Debug)]
 pub struct UserDataMetatable(pub(crate) Table);
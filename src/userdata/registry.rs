@@ -12,10 +12,10 @@ use std::string::String as StdString;
 use crate::error::{Error, Result};
 use crate::state::{Lua, LuaGuard};
 use crate::traits::{FromLua, FromLuaMulti, IntoLua, IntoLuaMulti};
-use crate::types::{Callback, MaybeSend};
+use crate::types::{Callback, MaybeSend, MaybeSync};
 use crate::userdata::{
-    borrow_userdata_scoped, borrow_userdata_scoped_mut, AnyUserData, MetaMethod, TypeIdHints, UserData,
-    UserDataFields, UserDataMethods,
+    borrow_userdata_scoped, borrow_userdata_scoped_mut, track_userdata_borrow, userdata_borrow_error,
+    AnyUserData, MetaMethod, TypeIdHints, UserData, UserDataFields, UserDataMethods,
 };
 use crate::util::short_type_name;
 use crate::value::Value;
@@ -100,6 +100,16 @@ impl<T: 'static> UserDataRegistry<T> {
     pub(crate) fn new(lua: &Lua) -> Self {
         Self::with_type(lua, UserDataType::Shared(TypeIdHints::new::<T>()))
     }
+
+    /// Adds a regular method which returns a new userdata holding a clone of `self`.
+    ///
+    /// The new userdata uses the same metatable as the one registered for `T`.
+    pub fn add_clone_method(&mut self, name: impl Into<StdString>)
+    where
+        T: Clone + MaybeSend + MaybeSync,
+    {
+        self.add_method(name, |lua, this, ()| lua.create_any_userdata(this.clone()));
+    }
 }
 
 impl<T> UserDataRegistry<T> {
@@ -161,9 +171,12 @@ impl<T> UserDataRegistry<T> {
                 #[rustfmt::skip]
                 UserDataType::Shared(type_hints) => {
                     let type_id = try_self_arg!(rawlua.get_userdata_type_id::<T>(state, self_index));
-                    try_self_arg!(borrow_userdata_scoped(state, self_index, type_id, type_hints, |ud| {
+                    let (extra, ud_ptr) = (rawlua.extra(), ffi::lua_topointer(state, self_index));
+                    let res = borrow_userdata_scoped(state, self_index, type_id, type_hints, |ud| {
+                        let _guard = track_userdata_borrow(extra, ud_ptr, &name);
                         method(rawlua.lua(), ud, args?)?.push_into_specified_stack_multi(rawlua, state)
-                    }))
+                    });
+                    try_self_arg!(res.map_err(|err| userdata_borrow_error(extra, ud_ptr, err)))
                 }
             }
         })
@@ -201,9 +214,12 @@ impl<T> UserDataRegistry<T> {
                 #[rustfmt::skip]
                 UserDataType::Shared(type_hints) => {
                     let type_id = try_self_arg!(rawlua.get_userdata_type_id::<T>(state, self_index));
-                    try_self_arg!(borrow_userdata_scoped(state, self_index, type_id, type_hints, |ud| {
+                    let (extra, ud_ptr) = (rawlua.extra(), ffi::lua_topointer(state, self_index));
+                    let res = borrow_userdata_scoped(state, self_index, type_id, type_hints, |ud| {
+                        let _guard = track_userdata_borrow(extra, ud_ptr, &name);
                         method(rawlua.lua(), ud, args?)?.push_into_specified_stack_multi(rawlua, state)
-                    }))
+                    });
+                    try_self_arg!(res.map_err(|err| userdata_borrow_error(extra, ud_ptr, err)))
                 }
             }
         })
@@ -240,9 +256,12 @@ impl<T> UserDataRegistry<T> {
                 #[rustfmt::skip]
                 UserDataType::Shared(type_hints) => {
                     let type_id = try_self_arg!(rawlua.get_userdata_type_id::<T>(state, self_index));
-                    try_self_arg!(borrow_userdata_scoped_mut(state, self_index, type_id, type_hints, |ud| {
+                    let (extra, ud_ptr) = (rawlua.extra(), ffi::lua_topointer(state, self_index));
+                    let res = borrow_userdata_scoped_mut(state, self_index, type_id, type_hints, |ud| {
+                        let _guard = track_userdata_borrow(extra, ud_ptr, &name);
                         method(rawlua.lua(), ud, args?)?.push_into_specified_stack_multi(rawlua, state)
-                    }))
+                    });
+                    try_self_arg!(res.map_err(|err| userdata_borrow_error(extra, ud_ptr, err)))
                 }
             }
         })
@@ -280,9 +299,12 @@ impl<T> UserDataRegistry<T> {
                 #[rustfmt::skip]
                 UserDataType::Shared(type_hints) => {
                     let type_id = try_self_arg!(rawlua.get_userdata_type_id::<T>(state, self_index));
-                    try_self_arg!(borrow_userdata_scoped_mut(state, self_index, type_id, type_hints, |ud| {
+                    let (extra, ud_ptr) = (rawlua.extra(), ffi::lua_topointer(state, self_index));
+                    let res = borrow_userdata_scoped_mut(state, self_index, type_id, type_hints, |ud| {
+                        let _guard = track_userdata_borrow(extra, ud_ptr, &name);
                         method(rawlua.lua(), ud, args?)?.push_into_specified_stack_multi(rawlua, state)
-                    }))
+                    });
+                    try_self_arg!(res.map_err(|err| userdata_borrow_error(extra, ud_ptr, err)))
                 }
             }
         })
@@ -319,9 +341,12 @@ impl<T> UserDataRegistry<T> {
                 #[rustfmt::skip]
                 UserDataType::Shared(type_hints) => {
                     let type_id = try_self_arg!(rawlua.get_userdata_type_id::<T>(state, self_index));
-                    try_self_arg!(borrow_userdata_scoped(state, self_index, type_id, type_hints, |ud| {
+                    let (extra, ud_ptr) = (rawlua.extra(), ffi::lua_topointer(state, self_index));
+                    let res = borrow_userdata_scoped(state, self_index, type_id, type_hints, |ud| {
+                        let _guard = track_userdata_borrow(extra, ud_ptr, &name);
                         method(rawlua.lua(), ud, name, args?)?.push_into_specified_stack_multi(rawlua, state)
-                    }))
+                    });
+                    try_self_arg!(res.map_err(|err| userdata_borrow_error(extra, ud_ptr, err)))
                 }
             }
         })
@@ -360,9 +385,12 @@ impl<T> UserDataRegistry<T> {
                 #[rustfmt::skip]
                 UserDataType::Shared(type_hints) => {
                     let type_id = try_self_arg!(rawlua.get_userdata_type_id::<T>(state, self_index));
-                    try_self_arg!(borrow_userdata_scoped_mut(state, self_index, type_id, type_hints, |ud| {
+                    let (extra, ud_ptr) = (rawlua.extra(), ffi::lua_topointer(state, self_index));
+                    let res = borrow_userdata_scoped_mut(state, self_index, type_id, type_hints, |ud| {
+                        let _guard = track_userdata_borrow(extra, ud_ptr, &name);
                         method(rawlua.lua(), ud, name, args?)?.push_into_specified_stack_multi(rawlua, state)
-                    }))
+                    });
+                    try_self_arg!(res.map_err(|err| userdata_borrow_error(extra, ud_ptr, err)))
                 }
             }
         })
@@ -574,6 +602,24 @@ impl<T> UserDataRegistry<T> {
     }
 }
 
+// Wraps a metamethod callback to check (in debug builds) that it returns the number of values
+// expected by Lua, unless the return type already guarantees it
+fn check_meta_method_results<R: IntoLuaMulti>(name: &str, callback: Callback) -> Callback {
+    let expected = match MetaMethod::expected_results(name) {
+        Some(expected) if cfg!(debug_assertions) && R::expected_len() != Some(expected) => expected,
+        _ => return callback,
+    };
+    let name = name.to_string();
+    Box::new(move |rawlua, nargs| {
+        let nresults = callback(rawlua, nargs)?;
+        if nresults as usize != expected {
+            let msg = format!("metamethod '{name}' must return {expected} value(s), got {nresults}");
+            return Err(Error::runtime(msg));
+        }
+        Ok(nresults)
+    })
+}
+
 // Returns function name for the type `T`, without the module path
 fn get_function_name<T>(name: &str) -> StdString {
     format!("{}.{name}", short_type_name::<T>())
@@ -834,7 +880,7 @@ impl<T> UserDataMethods<T> for UserDataRegistry<T> {
         R: IntoLuaMulti,
     {
         let name = name.into();
-        let callback = self.box_method(&name, method);
+        let callback = check_meta_method_results::<R>(&name, self.box_method(&name, method));
         self.raw.meta_methods.push((name, callback));
     }
 
@@ -845,7 +891,7 @@ impl<T> UserDataMethods<T> for UserDataRegistry<T> {
         R: IntoLuaMulti,
     {
         let name = name.into();
-        let callback = self.box_method_mut(&name, method);
+        let callback = check_meta_method_results::<R>(&name, self.box_method_mut(&name, method));
         self.raw.meta_methods.push((name, callback));
     }
 
@@ -856,7 +902,7 @@ impl<T> UserDataMethods<T> for UserDataRegistry<T> {
         R: IntoLuaMulti,
     {
         let name = name.into();
-        let callback = self.box_function(&name, function);
+        let callback = check_meta_method_results::<R>(&name, self.box_function(&name, function));
         self.raw.meta_methods.push((name, callback));
     }
 
@@ -867,7 +913,7 @@ impl<T> UserDataMethods<T> for UserDataRegistry<T> {
         R: IntoLuaMulti,
     {
         let name = name.into();
-        let callback = self.box_function_mut(&name, function);
+        let callback = check_meta_method_results::<R>(&name, self.box_function_mut(&name, function));
         self.raw.meta_methods.push((name, callback));
     }
 }
@@ -1,9 +1,10 @@
 use std::any::TypeId;
-use std::os::raw::c_int;
+use std::os::raw::{c_int, c_void};
 use std::ptr;
 
 use super::UserDataStorage;
-use crate::error::{Error, Result};
+use crate::error::{Error, ErrorContext, Result};
+use crate::state::ExtraData;
 use crate::util::{get_userdata, rawget_field, rawset_field, take_userdata};
 
 // Userdata type hints,  used to match types of wrapped userdata
@@ -201,6 +202,54 @@ pub(crate) unsafe fn borrow_userdata_scoped_mut<T, R>(
     }
 }
 
+// Records (when borrow diagnostics are enabled) that the userdata at `ptr` is borrowed by the
+// method `name` until the returned guard is dropped.
+pub(crate) unsafe fn track_userdata_borrow(
+    extra: *mut ExtraData,
+    ptr: *const c_void,
+    name: &str,
+) -> Option<UserDataBorrowGuard> {
+    let borrows = (*extra).borrow_diagnostics.as_mut()?;
+    borrows.entry(ptr).or_default().push(name.to_string());
+    Some(UserDataBorrowGuard { extra, ptr })
+}
+
+pub(crate) struct UserDataBorrowGuard {
+    extra: *mut ExtraData,
+    ptr: *const c_void,
+}
+
+impl Drop for UserDataBorrowGuard {
+    fn drop(&mut self) {
+        unsafe {
+            if let Some(borrows) = (*self.extra).borrow_diagnostics.as_mut() {
+                if let Some(names) = borrows.get_mut(&self.ptr) {
+                    names.pop();
+                    if names.is_empty() {
+                        borrows.remove(&self.ptr);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Adds the name of the method holding the outstanding borrow of the userdata at `ptr` to the
+// borrow error (if borrow diagnostics are enabled).
+pub(crate) unsafe fn userdata_borrow_error(extra: *mut ExtraData, ptr: *const c_void, err: Error) -> Error {
+    if !matches!(err, Error::UserDataBorrowError | Error::UserDataBorrowMutError) {
+        return err;
+    }
+    let holder = (*extra)
+        .borrow_diagnostics
+        .as_ref()
+        .and_then(|b| b.get(&ptr)?.last());
+    match holder {
+        Some(name) => err.context(format!("userdata is already borrowed by method '{name}'")),
+        None => err,
+    }
+}
+
 // Populates the given table with the appropriate members to be a userdata metatable for the given
 // type. This function takes the given table at the `metatable` index, and adds an appropriate
 // `__gc` member to it for the given type and a `__metatable` entry to protect the table from script
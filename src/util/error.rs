@@ -146,6 +146,7 @@ pub(crate) unsafe fn pop_error(state: *mut ffi::lua_State, err_code: c_int) -> E
 
             let err_string = to_string(state, -1);
             ffi::lua_pop(state, 1);
+            let err_string = remap_source_lines(state, err_string);
 
             match err_code {
                 ffi::LUA_ERRRUN => Error::RuntimeError(err_string),
@@ -155,6 +156,7 @@ pub(crate) unsafe fn pop_error(state: *mut ffi::lua_State, err_code: c_int) -> E
                         // stock Lua REPL does.
                         incomplete_input: err_string.ends_with("<eof>") || err_string.ends_with("'<eof>'"),
                         message: err_string,
+                        chunk_name: None,
                     }
                 }
                 ffi::LUA_ERRERR => {
@@ -173,6 +175,22 @@ pub(crate) unsafe fn pop_error(state: *mut ffi::lua_State, err_code: c_int) -> E
     }
 }
 
+// Translates chunk locations in the error message using source maps attached to loaded chunks.
+unsafe fn remap_source_lines(state: *mut ffi::lua_State, message: String) -> String {
+    let extra = crate::state::ExtraData::get(state);
+    if extra.is_null() {
+        return message;
+    }
+    let mut message = message;
+    for (source, (orig_source, map)) in (*extra).source_maps.lock().iter() {
+        // Only locations of the mapped chunks are translated
+        if message.contains(source.as_str()) {
+            message = map.remap(source, orig_source, &message);
+        }
+    }
+    message
+}
+
 // Call a function that calls into the Lua API and may trigger a Lua error (longjmp) in a safe way.
 // Wraps the inner function in a call to `lua_pcall`, so the inner function only has access to a
 // limited lua stack. `nargs` is the same as the the parameter to `lua_pcall`, and `nresults` is
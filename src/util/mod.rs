@@ -10,6 +10,8 @@ pub(crate) use error::{
     protect_lua_closure, WrappedFailure,
 };
 pub(crate) use path::parse_path as parse_lookup_path;
+#[cfg(feature = "luau")]
+pub(crate) use short_names::short_function_name;
 pub(crate) use short_names::short_type_name;
 pub(crate) use types::TypeKey;
 #[cfg(feature = "dynamic-userdata")]
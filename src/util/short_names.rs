@@ -56,6 +56,38 @@ pub(crate) fn short_type_name<T: ?Sized>() -> String {
     parsed_name
 }
 
+/// Returns a readable name of a function or closure type `F` without module paths.
+///
+/// Generic arguments and `{{closure}}` segments are skipped, so a closure defined inside of the
+/// `my::Foo<T>::setup` method is named `setup`.
+#[cfg(feature = "luau")]
+pub(crate) fn short_function_name<F: ?Sized>() -> &'static str {
+    let name = type_name::<F>();
+
+    // Split the path at `::` separators outside of generic argument lists (`<..>`)
+    let (mut depth, mut end) = (0usize, name.len());
+    let mut segments = Vec::new();
+    let bytes = name.as_bytes();
+    for i in (0..bytes.len()).rev() {
+        match bytes[i] {
+            // Skip `->` in function pointer types
+            b'>' if i == 0 || bytes[i - 1] != b'-' => depth += 1,
+            b'<' => depth = depth.saturating_sub(1),
+            b':' if depth == 0 && i > 0 && bytes[i - 1] == b':' => {
+                segments.push(&name[i + 1..end]);
+                end = i - 1;
+            }
+            _ => {}
+        }
+    }
+    segments.push(&name[..end]);
+
+    let segment = (segments.into_iter())
+        .find(|segment| *segment != "{{closure}}")
+        .unwrap_or_default();
+    segment.split('<').next().unwrap_or_default()
+}
+
 #[inline(always)]
 fn collapse_type_name(segment: &str) -> &str {
     segment.rsplit("::").next().unwrap()
@@ -84,4 +116,32 @@ mod tests {
         assert_eq!(short_type_name::<MyDataT<&str>>(), "MyDataT<&str>");
         assert_eq!(short_type_name::<(&MyData, [MyData])>(), "(MyData, [MyData])");
     }
+
+    #[cfg(feature = "luau")]
+    fn my_function() {}
+
+    #[cfg(feature = "luau")]
+    #[test]
+    fn function_names() {
+        use super::short_function_name;
+
+        fn name_of<F>(_: &F) -> &'static str {
+            short_function_name::<F>()
+        }
+
+        assert_eq!(name_of(&my_function), "my_function");
+        let closure = || {};
+        assert_eq!(name_of(&closure), "function_names");
+        let nested = || || {};
+        assert_eq!(name_of(&nested()), "function_names");
+
+        struct Foo<T>(T);
+        impl<T> Foo<T> {
+            fn method() -> impl Fn() {
+                || {}
+            }
+        }
+        assert_eq!(name_of(&Foo::<Vec<String>>::method()), "method");
+        assert_eq!(name_of(&Foo::<fn() -> i32>::method), "method");
+    }
 }
@@ -65,10 +65,10 @@ impl TypeKey for NamecallMapUpvalue {
 }
 
 #[cfg(not(feature = "luau"))]
-impl TypeKey for crate::types::HookCallback {
+impl TypeKey for crate::types::ThreadHook {
     #[inline(always)]
     fn type_key() -> *const c_void {
-        static HOOK_CALLBACK_TYPE_KEY: u8 = 0;
-        &HOOK_CALLBACK_TYPE_KEY as *const u8 as *const c_void
+        static THREAD_HOOK_TYPE_KEY: u8 = 0;
+        &THREAD_HOOK_TYPE_KEY as *const u8 as *const c_void
     }
 }
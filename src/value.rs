@@ -110,6 +110,20 @@ impl Value {
         }
     }
 
+    /// Returns the registered type name of a userdata value.
+    ///
+    /// The name is taken from the `__type` (Luau) or `__name` metatable field, which by default is
+    /// set to the Rust type name when registering a userdata type. Returns `None` for
+    /// non-userdata values or if no name is set.
+    ///
+    /// This is useful for diagnostics, eg. to report `"Vector3"` instead of `"userdata"`.
+    pub fn userdata_type_name(&self) -> Option<StdString> {
+        match self {
+            Value::UserData(ud) => ud.type_name().ok().flatten(),
+            _ => None,
+        }
+    }
+
     /// Compares two values for equality.
     ///
     /// Equality comparisons do not convert strings to numbers or vice versa.
@@ -740,6 +754,15 @@ impl<'a> SerializableValue<'a> {
         self.options.detect_mixed_tables = enabled;
         self
     }
+
+    /// If true, Luau buffers will be encoded as base64 strings.
+    ///
+    /// Default: **false**
+    #[must_use]
+    pub fn buffers_as_base64(mut self, enabled: bool) -> Self {
+        self.options.buffers_as_base64 = enabled;
+        self
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -768,6 +791,10 @@ impl Serialize for SerializableValue<'_> {
                 ud.serialize(serializer)
             }
             #[cfg(feature = "luau")]
+            Value::Buffer(buf) if self.options.buffers_as_base64 => {
+                serializer.serialize_str(&buf.to_base64())
+            }
+            #[cfg(feature = "luau")]
             Value::Buffer(buf) => buf.serialize(serializer),
             Value::Function(_)
             | Value::Thread(_)
@@ -21,6 +21,7 @@ fn test_buffer() -> Result<()> {
 
     let buf2 = lua.load("buffer.fromstring('hello')").eval::<Value>()?;
     assert_ne!(buf1, buf2);
+    assert!(buf1.as_buffer().unwrap().content_eq(buf2.as_buffer().unwrap()));
 
     // Check that we can pass buffer type to Lua
     let buf1 = buf1.as_buffer().unwrap();
@@ -37,6 +38,7 @@ fn test_buffer() -> Result<()> {
 
     let buf3 = lua.create_buffer(b"")?;
     assert!(buf3.is_empty());
+    assert!(!buf3.content_eq(buf1));
 
     let p = buf3.to_pointer();
     assert!(!p.is_null());
@@ -125,3 +127,72 @@ fn test_buffer_cursor() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_buffer_typed_slices() -> Result<()> {
+    let lua = Lua::new();
+
+    let buf = lua
+        .load("local b = buffer.create(12); buffer.writef32(b, 4, 1.5); buffer.writei32(b, 8, -7); return b")
+        .eval::<mluau::Buffer>()?;
+
+    if cfg!(target_endian = "little") {
+        assert_eq!(buf.with_f32_slice(|data| data[1])?, 1.5);
+        assert_eq!(buf.with_i32_slice(|data| data[2])?, -7);
+    }
+    buf.with_f32_slice_mut(|data| data[0] = 2.0)?;
+    assert_eq!(buf.with_f32_slice(|data| data.len())?, 3);
+    assert_eq!(buf.with_f32_slice(|data| data[0])?, 2.0);
+
+    // Mutable views cannot overlap other views of the same buffer
+    let buf2 = buf.clone();
+    let res = buf.with_f32_slice_mut(|_| buf2.with_i32_slice(|_| ()))?;
+    assert!(matches!(res, Err(mluau::Error::RecursiveMutCallback)));
+    let res = buf.with_f32_slice(|_| buf2.with_i32_slice_mut(|_| ()))?;
+    assert!(matches!(res, Err(mluau::Error::RecursiveMutCallback)));
+    let len = buf.with_f32_slice(|_| buf2.with_i32_slice(|data| data.len()))??;
+    assert_eq!(len, 3);
+    buf.with_i32_slice_mut(|_| ())?;
+
+    // Length must be a multiple of the element size
+    let buf = lua.create_buffer_with_capacity(6)?;
+    assert!(buf.with_i32_slice(|_| ()).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_with_mut_slice() -> Result<()> {
+    let lua = Lua::new();
+
+    let mut samples = [0.5f32, -1.0, 2.0];
+    let gain = lua
+        .load(
+            r#"
+            return function(buf)
+                for i = 0, buffer.len(buf) - 4, 4 do
+                    buffer.writef32(buf, i, buffer.readf32(buf, i) * 2)
+                end
+            end
+        "#,
+        )
+        .eval::<mluau::Function>()?;
+    lua.with_mut_slice(&mut samples, |buf| gain.call::<()>(buf))?;
+    assert_eq!(samples, [1.0, -2.0, 4.0]);
+
+    // The buffer is not usable after the scope
+    let buf = lua.with_mut_slice(&mut samples, Ok)?;
+    assert_eq!(buf.to_vec(), vec![0; 12]);
+    buf.write_bytes(0, &[1, 2, 3, 4]);
+    assert_eq!(samples, [1.0, -2.0, 4.0]);
+
+    // Nothing is copied back on error
+    let res = lua.with_mut_slice(&mut samples, |buf| {
+        buf.write_bytes(0, &[0; 4]);
+        Err::<(), _>(mluau::Error::runtime("failed"))
+    });
+    assert!(res.is_err());
+    assert_eq!(samples, [1.0, -2.0, 4.0]);
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use std::{fs, io};
 
-use mluau::{Chunk, ChunkMode, Lua, Result};
+use mluau::{Chunk, ChunkMode, Error, Lua, Result, SourceMap, Value};
 
 #[test]
 fn test_chunk_methods() -> Result<()> {
@@ -17,6 +17,163 @@ fn test_chunk_methods() -> Result<()> {
     assert_eq!(chunk3.mode(), ChunkMode::Text);
     assert_eq!(chunk3.call::<i32>(())?, 987);
 
+    let func = lua.load("return a").into_function_with_env(env)?;
+    assert_eq!(func.call::<i32>(())?, 987);
+
+    let values = lua.load("1, 'two', nil").eval_all()?;
+    assert_eq!(values.len(), 3);
+    assert_eq!(values[0], Value::Integer(1));
+    assert_eq!(values[2], Value::Nil);
+    assert!(lua.load("local x = 1").eval_all()?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_chunk_with_globals() -> Result<()> {
+    let lua = Lua::new();
+
+    let res = lua
+        .load("x = 1; return tostring(greeting) .. ' ' .. name")
+        .with_globals([("greeting", "hello"), ("name", "world")])
+        .eval::<String>()?;
+    assert_eq!(res, "hello world");
+    // Real globals are not modified
+    assert_eq!(lua.globals().get::<Value>("greeting")?, Value::Nil);
+    assert_eq!(lua.globals().get::<Value>("x")?, Value::Nil);
+
+    // Layered on top of an explicit environment
+    let env = lua.create_table_from([("a", 1)])?;
+    let res = lua
+        .load("return a + b")
+        .set_environment(env)
+        .with_globals([("b", 2)])
+        .eval::<i32>()?;
+    assert_eq!(res, 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_chunk_source_map() -> Result<()> {
+    let lua = Lua::new();
+
+    let map = SourceMap::new().add_line(2, "main.dsl", 10);
+    assert_eq!(map.get(2), Some(("main.dsl", 10)));
+    assert_eq!(map.get(1), None);
+
+    let err = lua
+        .load("local x = 1\nerror('boom')")
+        .set_name("=generated")
+        .set_source_map(map)
+        .exec()
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("main.dsl:10: boom"), "unexpected error: {err}");
+    assert!(!err.contains("generated:2:"), "unexpected error: {err}");
+
+    // Chunks without source map are not affected
+    let err = lua
+        .load("error('boom')")
+        .set_name("=other")
+        .exec()
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("other:1: boom"), "unexpected error: {err}");
+
+    // Chunks with the same name do not share source maps
+    let f1 = lua
+        .load("local x = 1\nerror('first')")
+        .set_name("=same")
+        .set_source_map(SourceMap::new().add_line(2, "first.dsl", 20))
+        .into_function()?;
+    let f2 = lua
+        .load("error('second')")
+        .set_name("=same")
+        .set_source_map(SourceMap::new().add_line(1, "second.dsl", 5))
+        .into_function()?;
+    let err = f1.call::<()>(()).unwrap_err().to_string();
+    assert!(err.contains("first.dsl:20: first"), "unexpected error: {err}");
+    let err = f2.call::<()>(()).unwrap_err().to_string();
+    assert!(err.contains("second.dsl:5: second"), "unexpected error: {err}");
+
+    // Unmapped lines are reported against the original chunk name
+    let err = lua
+        .load("\nerror('unmapped')")
+        .set_name("=same")
+        .set_source_map(SourceMap::new())
+        .exec()
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("same:2: unmapped"), "unexpected error: {err}");
+
+    Ok(())
+}
+
+#[test]
+fn test_check_syntax() -> Result<()> {
+    let lua = Lua::new();
+
+    lua.check_syntax("x = 1 local function f() return x end")?;
+    // Nothing is executed
+    assert_eq!(lua.globals().get::<Value>("x")?, Value::Nil);
+
+    match lua.check_syntax("local a = 1\nlocal b = ") {
+        Err(Error::SyntaxError {
+            message,
+            incomplete_input,
+            ..
+        }) => {
+            assert!(message.contains('2'), "unexpected message: {message}");
+            assert!(incomplete_input);
+        }
+        r => panic!("expected SyntaxError, got {r:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_chunk_exec_timed() -> Result<()> {
+    let lua = Lua::new();
+
+    let elapsed = lua
+        .load("local t = os.clock() while os.clock() - t < 0.01 do end")
+        .exec_timed()?;
+    assert!(elapsed.as_secs_f64() >= 0.005);
+
+    // Errors are propagated
+    assert!(lua.load("error('boom')").exec_timed().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_chunk_name_helpers() -> Result<()> {
+    let lua = Lua::new();
+
+    let chunk = lua.load("error('boom')").set_chunk_name_literal("template");
+    assert_eq!(chunk.name(), "=template");
+    let err = chunk.exec().unwrap_err().to_string();
+    assert!(err.contains("template:1: boom"), "unexpected error: {err}");
+
+    let chunk = lua.load("return 1").set_chunk_name_file("scripts/init.lua");
+    assert_eq!(chunk.name(), "@scripts/init.lua");
+    assert_eq!(chunk.eval::<i32>()?, 1);
+
+    // Names that would be truncated are rejected
+    let long_name = "x".repeat(300);
+    let err = lua
+        .load("return 1")
+        .set_chunk_name_literal(&long_name)
+        .exec()
+        .unwrap_err();
+    assert!(err.to_string().contains("chunk name is too long"));
+
+    // The error is kept when the environment is replaced
+    let chunk = lua.load("return 1").set_chunk_name_file(&long_name);
+    assert!(chunk.set_environment(lua.create_table()?).eval::<i32>().is_err());
+
     Ok(())
 }
 
@@ -163,6 +320,56 @@ fn test_compiler_library_constants() {
     assert_eq!(const_str.unwrap(), "value1");
 }
 
+#[cfg(feature = "luau")]
+#[test]
+fn test_default_compiler() -> Result<()> {
+    use mluau::Compiler;
+
+    let lua = Lua::new();
+    assert!(lua.compiler().is_none());
+
+    // Chunk is created before the default compiler is set
+    let chunk = lua.load("return mylib.const_num");
+
+    let compiler = Compiler::new()
+        .set_optimization_level(2)
+        .add_library_constant("mylib.const_num", 123.0);
+    lua.set_compiler(compiler);
+    assert!(lua.compiler().is_some());
+
+    assert_eq!(chunk.eval::<f64>()?, 123.0);
+
+    // Syntax errors are reported with the chunk name
+    match lua.load("return +").set_name("=bad").exec() {
+        Err(Error::SyntaxError { message, .. }) => assert!(message.starts_with("bad:1:")),
+        res => panic!("expected syntax error, got {res:?}"),
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "luau")]
+#[test]
+fn test_lua_compile() -> Result<()> {
+    let lua = Lua::new();
+    let compiler = mluau::Compiler::new()
+        .set_optimization_level(2)
+        .add_library_constant("mylib.const_num", 7.0);
+    lua.set_compiler(compiler);
+
+    // Bytecode is produced with the default compiler and can be loaded elsewhere
+    let bytecode = lua.compile("return mylib.const_num * 2")?;
+    let other = Lua::new();
+    assert_eq!(other.load(&bytecode).eval::<f64>()?, 14.0);
+
+    assert!(matches!(
+        lua.compile("return +"),
+        Err(mluau::Error::SyntaxError { .. })
+    ));
+
+    Ok(())
+}
+
 #[test]
 fn test_chunk_wrap() -> Result<()> {
     let lua = Lua::new();
@@ -179,3 +386,39 @@ fn test_chunk_wrap() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_chunk_load_owned() -> Result<()> {
+    let lua = Lua::new();
+
+    let source = format!("return {} + {}", 40, 2);
+    assert_eq!(lua.load_owned(source).eval::<i32>()?, 42);
+
+    let source = b"return ...".to_vec();
+    let f = lua.load_owned(source).set_name("owned").into_function()?;
+    assert_eq!(f.call::<String>("hello")?, "hello");
+
+    Ok(())
+}
+
+#[test]
+fn test_default_chunk_env() -> Result<()> {
+    let lua = Lua::new();
+
+    let sandbox = lua.create_table_from([("x", 1)])?;
+    lua.set_default_chunk_env(Some(sandbox.clone()));
+    assert_eq!(lua.default_chunk_env().as_ref(), Some(&sandbox));
+
+    lua.load("y = x + 1").exec()?;
+    assert_eq!(sandbox.get::<i32>("y")?, 2);
+    assert!(lua.globals().get::<Option<i32>>("y")?.is_none());
+
+    // Explicit environment takes precedence
+    let env = lua.create_table_from([("x", 10)])?;
+    assert_eq!(lua.load("return x").set_environment(env).eval::<i32>()?, 10);
+
+    lua.set_default_chunk_env(None);
+    assert!(lua.load("return x").eval::<Option<i32>>()?.is_none());
+
+    Ok(())
+}
@@ -552,6 +552,43 @@ fn test_bstring_from_lua_buffer() -> Result<()> {
     Ok(())
 }
 
+#[cfg(feature = "bytes")]
+#[test]
+fn test_bytes_into_from_lua() -> Result<()> {
+    let lua = Lua::new();
+
+    let value = lua.convert::<Value>(bytes::Bytes::from_static(b"hello\0world"))?;
+    #[cfg(feature = "luau")]
+    assert!(value.is_buffer());
+    #[cfg(not(feature = "luau"))]
+    assert!(value.is_string());
+    assert_eq!(lua.convert::<bytes::Bytes>(value)?, &b"hello\0world"[..]);
+
+    // Test from stack
+    let f = lua.create_function(|_, b: bytes::Bytes| Ok(b.len()))?;
+    assert_eq!(f.call::<usize>("hello")?, 5);
+
+    Ok(())
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn test_uuid_into_from_lua() -> Result<()> {
+    let lua = Lua::new();
+
+    let id = uuid::Uuid::from_u128(0x67e55044_10b1_426f_9247_bb680e5fe0c8);
+    let value = lua.convert::<Value>(id)?;
+    assert_eq!(value.to_string()?, "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    assert_eq!(lua.convert::<uuid::Uuid>(value)?, id);
+
+    // Malformed input
+    let err = lua.convert::<uuid::Uuid>("not-a-uuid").unwrap_err();
+    assert!(matches!(err, Error::FromLuaConversionError { .. }));
+    assert!(lua.convert::<uuid::Uuid>(123).is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_osstring_into_from_lua() -> Result<()> {
     let lua = Lua::new();
@@ -78,3 +78,86 @@ fn test_gc_interrupt() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_frame_info() -> Result<()> {
+    let lua = Lua::new();
+
+    assert!(lua.frame_info(0).is_none());
+
+    let f = lua.create_function(|lua, ()| {
+        let info = lua.frame_info(1).expect("caller frame must exist");
+        assert!(lua.frame_info(100).is_none());
+        Ok((info.name, info.short_src, info.line_defined))
+    })?;
+    lua.globals().set("report", f)?;
+    let (name, short_src, line_defined) = lua
+        .load(
+            r#"
+        local function inner()
+            local name, src, line = report()
+            return name, src, line
+        end
+        return inner()
+    "#,
+        )
+        .set_name("=frames")
+        .eval::<(Option<String>, Option<String>, Option<usize>)>()?;
+    assert_eq!(name.as_deref(), Some("inner"));
+    assert_eq!(short_src.as_deref(), Some("frames"));
+    assert_eq!(line_defined, Some(2));
+
+    Ok(())
+}
+
+#[test]
+fn test_current_location() -> Result<()> {
+    let lua = Lua::new();
+
+    assert_eq!(lua.current_location(), None);
+
+    let f =
+        lua.create_function(|lua, ()| Ok(lua.current_location().map(|(src, line)| (src, line as i64))))?;
+    lua.globals().set("where", f)?;
+    let (src, line) = lua
+        .load(
+            r#"
+        local x = 1
+        return where()
+    "#,
+        )
+        .set_name("=location")
+        .eval::<(String, i64)>()?;
+    assert_eq!(src, "location");
+    assert_eq!(line, 3);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "luau")]
+fn test_current_location_interrupt() -> Result<()> {
+    use mluau::VmState;
+    use std::sync::Mutex;
+
+    let lua = Lua::new();
+
+    let location = Arc::new(Mutex::new(None));
+    let location2 = location.clone();
+    lua.set_interrupt(move |lua| {
+        if let Some(loc) = lua.current_location() {
+            *location2.lock().unwrap() = Some(loc);
+        }
+        Ok(VmState::Continue)
+    });
+    lua.load("for i = 1, 10 do local _ = i end")
+        .set_name("=interrupted")
+        .exec()?;
+    lua.remove_interrupt();
+
+    let (src, line) = location.lock().unwrap().take().unwrap();
+    assert_eq!(src, "interrupted");
+    assert_eq!(line, 1);
+
+    Ok(())
+}
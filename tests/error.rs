@@ -3,6 +3,76 @@ use std::{fmt, io};
 
 use mluau::{Error, ErrorContext, Lua, LuaOptions, Result};
 
+#[test]
+fn test_create_error() -> Result<()> {
+    let lua = Lua::new();
+
+    let err = lua.create_error(Error::runtime("staged error"))?;
+    assert!(err.is_error());
+
+    let errors = lua.create_table()?;
+    errors.set("staged", err)?;
+    lua.globals().set("errors", errors)?;
+
+    let msg = lua
+        .load("local ok, err = pcall(error, errors.staged); assert(not ok); return tostring(err)")
+        .eval::<String>()?;
+    assert!(msg.contains("staged error"));
+
+    match lua.load("error(errors.staged)").exec() {
+        Err(Error::RuntimeError(msg)) => assert_eq!(msg, "staged error"),
+        r => panic!("expected RuntimeError, got {r:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_raise_error() -> Result<()> {
+    let lua = Lua::new();
+
+    let fail = lua.create_function(|_, ()| Err::<(), _>(Error::runtime("original error")))?;
+    let rethrow = lua.create_function(|lua, err: mluau::Value| Err::<(), _>(lua.raise(err)))?;
+    lua.globals().set("fail", fail)?;
+    lua.globals().set("rethrow", rethrow)?;
+
+    let res = lua.load("local _, err = pcall(fail); rethrow(err)").exec();
+    match res {
+        Err(Error::CallbackError { cause, .. }) => {
+            assert!(matches!(cause.as_ref(), Error::RuntimeError(msg) if msg == "original error"));
+        }
+        r => panic!("expected CallbackError, got {r:?}"),
+    }
+
+    // The re-raised error keeps the original traceback
+    let (err, rethrown) = lua
+        .load("local _, err = pcall(fail); return err, select(2, pcall(rethrow, err))")
+        .eval::<(mluau::Value, mluau::Value)>()?;
+    match (err, rethrown) {
+        (mluau::Value::Error(err), mluau::Value::Error(rethrown)) => match (*err, *rethrown) {
+            (
+                Error::CallbackError { traceback, cause },
+                Error::CallbackError {
+                    traceback: traceback2,
+                    cause: cause2,
+                },
+            ) => {
+                assert!(traceback.contains("stack traceback"));
+                assert_eq!(traceback, traceback2);
+                assert!(std::sync::Arc::ptr_eq(&cause, &cause2));
+            }
+            r => panic!("expected CallbackError pair, got {r:?}"),
+        },
+        r => panic!("expected error values, got {r:?}"),
+    }
+
+    // Non-error values are converted to runtime errors
+    let err = lua.raise(mluau::Value::Integer(123));
+    assert!(matches!(err, Error::RuntimeError(msg) if msg == "123"));
+
+    Ok(())
+}
+
 #[test]
 fn test_error_context() -> Result<()> {
     let lua = Lua::new();
@@ -77,6 +147,27 @@ fn test_error_chain() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_error_runtime_message() -> Result<()> {
+    let lua = Lua::new();
+
+    let func = lua.create_function(|_, ()| {
+        let err = Error::runtime("inner failure").context("while doing work");
+        Err::<(), _>(err)
+    })?;
+    let err = func.call::<()>(()).unwrap_err();
+    assert_eq!(err.runtime_message(), Some("inner failure"));
+
+    let err = lua.load("error('lua failure')").exec().unwrap_err();
+    assert!(err.runtime_message().unwrap().ends_with("lua failure"));
+    assert!(!err.runtime_message().unwrap().contains("stack traceback"));
+
+    let err = Error::external(io::Error::new(io::ErrorKind::Other, "other"));
+    assert_eq!(err.runtime_message(), None);
+
+    Ok(())
+}
+
 #[cfg(feature = "anyhow")]
 #[test]
 fn test_error_anyhow() -> Result<()> {
@@ -1,4 +1,9 @@
-use mluau::{Error, Function, Lua, Result, String, Table, Variadic};
+use std::string::String as StdString;
+
+use mluau::{
+    Error, FromLua, FromLuaMulti, Function, Lua, MultiValue, Result, StackArgs, String, Table, Value,
+    Variadic,
+};
 
 #[test]
 fn test_function_call() -> Result<()> {
@@ -194,6 +199,13 @@ fn test_function_info() -> Result<()> {
     assert_eq!(print_info.what, "C");
     assert_eq!(print_info.line_defined, None);
 
+    let main_info = lua.load("return 1").into_function()?.info();
+    assert_eq!(main_info.what, "main");
+
+    // Vararg functions defined at the start of a chunk are not main chunks
+    let vararg_info = lua.load("return function(...) end").eval::<Function>()?.info();
+    assert_eq!(vararg_info.what, "Lua");
+
     Ok(())
 }
 
@@ -377,6 +389,101 @@ fn test_function_wrap() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_function_wrap_with_upvalues() -> Result<()> {
+    let lua = Lua::new();
+
+    let config = lua.create_table_from([("prefix", "> ")])?;
+    let upvalues = vec![Value::Table(config.clone()), Value::Integer(2)];
+    let f = Function::wrap_with_upvalues(
+        |_, upvalues: &[Value], s: String| {
+            let prefix: String = upvalues[0].as_table().unwrap().get("prefix")?;
+            let n = upvalues[1].as_integer().unwrap() as usize;
+            Ok(format!("{}{}", prefix.to_str()?, s.to_str()?.repeat(n)))
+        },
+        upvalues,
+    );
+    lua.globals().set("f", f)?;
+    lua.load(r#"assert(f("ab") == "> abab")"#).exec().unwrap();
+
+    // Upvalues are shared Lua values
+    config.set("prefix", "< ")?;
+    lua.load(r#"assert(f("ab") == "< abab")"#).exec().unwrap();
+
+    // No upvalues
+    let f = Function::wrap_with_upvalues(|_, upvalues: &[Value], ()| Ok(upvalues.len()), Vec::new());
+    assert_eq!(lua.convert::<Function>(f)?.call::<usize>(())?, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_function_bad_argument_position() -> Result<()> {
+    let lua = Lua::new();
+
+    let f = lua.create_function(|_, (_s, _n): (String, Variadic<i64>)| Ok(()))?;
+    lua.globals().set("f", f)?;
+
+    // Each variadic argument reports its own position
+    let err = lua.load(r#"f("a", 1, 2, {})"#).exec().unwrap_err();
+    match err {
+        Error::CallbackError { cause, .. } => match cause.as_ref() {
+            Error::BadArgument { pos, .. } => assert_eq!(*pos, 4),
+            err => panic!("expected BadArgument, got {err:?}"),
+        },
+        err => panic!("expected CallbackError, got {err:?}"),
+    }
+
+    // Only conversion errors of custom multi-value types are wrapped
+    #[derive(Debug)]
+    struct Custom;
+    impl FromLuaMulti for Custom {
+        fn from_lua_multi(mut values: MultiValue, lua: &Lua) -> Result<Self> {
+            match values.pop_front() {
+                Some(Value::String(s)) if s == "oom" => Err(Error::MemoryError("oom".into())),
+                value => i64::from_lua(value.unwrap_or(Value::Nil), lua).map(|_| Custom),
+            }
+        }
+    }
+    let args = |v: Value| MultiValue::from_vec(vec![v]);
+    match Custom::from_lua_args(args(Value::Boolean(true)), 1, Some("f"), &lua) {
+        Err(Error::BadArgument { pos, cause, .. }) => {
+            assert_eq!(pos, 1);
+            assert!(matches!(cause.as_ref(), Error::FromLuaConversionError { .. }));
+        }
+        r => panic!("expected BadArgument, got {r:?}"),
+    }
+    let oom = Value::String(lua.create_string("oom")?);
+    match Custom::from_lua_args(args(oom), 1, Some("f"), &lua) {
+        Err(Error::MemoryError(_)) => {}
+        r => panic!("expected MemoryError, got {r:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_raw_function() -> Result<()> {
+    let lua = Lua::new();
+
+    let f = lua.create_raw_function(|_, args: StackArgs| {
+        let n = args.get::<usize>(0)?;
+        Ok((args.get::<Value>(n)?, args.len()))
+    })?;
+    assert_eq!(f.call::<(String, usize)>((2, "a", "b", "c"))?.1, 4);
+    assert_eq!(f.call::<(String, usize)>((2, "a", "b", "c"))?.0, "b");
+    assert_eq!(f.call::<(Value, usize)>(5)?, (Value::Nil, 1));
+
+    // Conversion errors report the argument position
+    let err = f.call::<()>("x").unwrap_err();
+    assert!(err.to_string().contains("bad argument #1"), "{err}");
+
+    let all = lua.create_raw_function(|_, args: StackArgs| args.to_multi_value())?;
+    assert_eq!(all.call::<(i32, i32, i32)>((1, 2, 3))?, (1, 2, 3));
+
+    Ok(())
+}
+
 #[test]
 fn test_function_wrap_raw() -> Result<()> {
     let lua = Lua::new();
@@ -420,3 +527,74 @@ fn test_function_wrap_raw() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_function_local() -> Result<()> {
+    let lua = Lua::new();
+
+    let counter = std::rc::Rc::new(std::cell::Cell::new(0));
+    let counter2 = counter.clone();
+    let func = lua.create_function_local(move |_, n: i32| {
+        counter2.set(counter2.get() + n);
+        Ok(counter2.get())
+    })?;
+    assert_eq!(func.call::<i32>(2)?, 2);
+    assert_eq!(func.call::<i32>(3)?, 5);
+    assert_eq!(counter.get(), 5);
+
+    #[cfg(feature = "send")]
+    std::thread::scope(|s| {
+        s.spawn(|| match func.call::<i32>(1) {
+            Err(Error::CallbackError { cause, .. }) => {
+                assert!(cause.to_string().contains("different thread"));
+            }
+            r => panic!("expected CallbackError, got {r:?}"),
+        });
+    });
+    assert_eq!(counter.get(), 5);
+
+    Ok(())
+}
+
+#[test]
+fn test_typed_function() -> Result<()> {
+    let lua = Lua::new();
+
+    let f = lua.create_typed_function(
+        &["number", "string?", "any"],
+        |_, (n, s, _): (f64, Option<StdString>, Value)| Ok(format!("{n}:{}", s.unwrap_or_default())),
+    )?;
+    lua.globals().set("f", f)?;
+
+    assert_eq!(lua.load("f(1, 'a', true)").eval::<StdString>()?, "1:a");
+    assert_eq!(lua.load("f(1, nil, {})").eval::<StdString>()?, "1:");
+
+    let check_err = |code: &str, pos: usize, message: &str| match lua.load(code).exec() {
+        Err(Error::CallbackError { cause, .. }) => match cause.as_ref() {
+            Error::BadArgument {
+                pos: err_pos, cause, ..
+            } => {
+                assert_eq!(*err_pos, pos);
+                assert_eq!(cause.to_string(), message);
+            }
+            err => panic!("expected BadArgument, got {err:?}"),
+        },
+        r => panic!("expected CallbackError, got {r:?}"),
+    };
+    check_err("f('x', 'a', 1)", 1, "number expected, got string");
+    check_err("f(1, 2, 1)", 2, "string expected, got number");
+    check_err("f(1, 'a')", 3, "any expected, got no value");
+
+    // Integers accept integral numbers only
+    let g = lua.create_typed_function(&["integer"], |_, n: i64| Ok(n))?;
+    lua.globals().set("g", g)?;
+    assert_eq!(lua.load("g(3)").eval::<i64>()?, 3);
+    assert!(lua.load("g(1.5)").exec().is_err());
+    assert!(lua.load("g('3')").exec().is_err());
+
+    // Unknown types are rejected upfront
+    let res = lua.create_typed_function(&["str"], |_, ()| Ok(()));
+    assert!(matches!(res, Err(Error::RuntimeError(msg)) if msg.contains("unknown type `str`")));
+
+    Ok(())
+}
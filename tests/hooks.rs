@@ -325,3 +325,165 @@ fn test_global_hook() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_global_hook_composition() -> Result<()> {
+    let lua = Lua::new();
+    assert!(lua.hook_triggers().is_none());
+
+    let lines = Arc::new(AtomicI64::new(0));
+    let lines2 = lines.clone();
+    let prev = lua.set_global_hook(HookTriggers::EVERY_LINE, move |_lua, _debug| {
+        lines2.fetch_add(1, Ordering::Relaxed);
+        Ok(VmState::Continue)
+    })?;
+    assert!(prev.is_none());
+    assert!(lua.hook_triggers().unwrap().every_line);
+
+    // Swap in a new hook that chains to the previous one
+    let calls = Arc::new(AtomicI64::new(0));
+    let calls2 = calls.clone();
+    let prev = lua.set_global_hook(HookTriggers::default(), |_, _| Ok(VmState::Continue))?;
+    let prev = prev.expect("previous hook");
+    let triggers = prev.triggers() | HookTriggers::ON_CALLS;
+    lua.set_global_hook(triggers, move |lua, debug| {
+        if debug.event() == DebugEvent::Call {
+            calls2.fetch_add(1, Ordering::Relaxed);
+        }
+        prev.call(lua, debug)
+    })?;
+    let triggers = lua.hook_triggers().unwrap();
+    assert!(triggers.every_line && triggers.on_calls);
+
+    lua.load("local function f() end\nf()").exec()?;
+    assert!(lines.load(Ordering::Relaxed) > 0);
+    assert!(calls.load(Ordering::Relaxed) > 0);
+
+    // Hooks taken from another Lua instance cannot be called
+    let lua2 = Lua::new();
+    lua2.set_global_hook(HookTriggers::EVERY_LINE, |_, _| Ok(VmState::Continue))?;
+    let other = lua2.set_global_hook(HookTriggers::default(), |_, _| Ok(VmState::Continue))?;
+    let other = other.expect("previous hook");
+    lua.set_global_hook(HookTriggers::EVERY_LINE, move |lua, debug| other.call(lua, debug))?;
+    match lua.load("local x = 1").exec() {
+        Err(Error::CallbackError { cause, .. }) => {
+            assert!(cause.to_string().contains("different Lua instance"));
+        }
+        r => panic!("expected CallbackError, got {r:?}"),
+    }
+
+    lua.remove_global_hook();
+    assert!(lua.hook_triggers().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_current_line_atomic() -> Result<()> {
+    let lua = Lua::new();
+    assert_eq!(lua.current_line_atomic(), None);
+
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let lines2 = lines.clone();
+    lua.set_hook(HookTriggers::EVERY_LINE, move |lua, debug| {
+        let published = lua.current_line_atomic().map(|l| l as usize);
+        assert_eq!(published, debug.current_line());
+        lines2.lock().unwrap().push(published.unwrap());
+        Ok(VmState::Continue)
+    })?;
+    lua.load("local x = 1\nlocal y = 2\nlocal z = 3").exec()?;
+
+    assert_eq!(*lines.lock().unwrap(), vec![1, 2, 3]);
+    assert_eq!(lua.current_line_atomic(), Some(3));
+
+    Ok(())
+}
+
+#[test]
+fn test_max_call_depth() -> Result<()> {
+    let lua = Lua::new();
+
+    // For LuaJIT disable JIT, as compiled code does not trigger hooks
+    #[cfg(feature = "luajit")]
+    lua.load("jit.off()").exec()?;
+
+    let lines = Arc::new(AtomicI64::new(0));
+    let lines2 = lines.clone();
+    lua.set_global_hook(HookTriggers::EVERY_LINE, move |_, debug| {
+        assert_eq!(debug.event(), DebugEvent::Line);
+        lines2.fetch_add(1, Ordering::Relaxed);
+        Ok(VmState::Continue)
+    })?;
+
+    lua.set_max_call_depth(50);
+    let rec = lua
+        .load("local function f(n) if n == 0 then return 0 end return 1 + f(n - 1) end return f")
+        .eval::<mluau::Function>()?;
+    assert_eq!(rec.call::<i64>(10)?, 10);
+    let err = rec.call::<i64>(100).unwrap_err();
+    assert!(
+        err.to_string().contains("out of Lua stack"),
+        "unexpected error: {err}"
+    );
+    // The global hook keeps receiving only the events it asked for
+    assert!(lines.load(Ordering::Relaxed) > 0);
+
+    lua.remove_max_call_depth();
+    assert_eq!(rec.call::<i64>(100)?, 100);
+
+    Ok(())
+}
+
+#[test]
+fn test_max_call_depth_with_hook() -> Result<()> {
+    let lua = Lua::new();
+
+    // For LuaJIT disable JIT, as compiled code does not trigger hooks
+    #[cfg(feature = "luajit")]
+    lua.load("jit.off()").exec()?;
+
+    let rec = lua
+        .load("local function f(n) if n == 0 then return 0 end return 1 + f(n - 1) end return f")
+        .eval::<mluau::Function>()?;
+
+    let lines = Arc::new(AtomicI64::new(0));
+    let lines2 = lines.clone();
+    lua.set_hook(HookTriggers::EVERY_LINE, move |_, debug| {
+        assert_eq!(debug.event(), DebugEvent::Line);
+        lines2.fetch_add(1, Ordering::Relaxed);
+        Ok(VmState::Continue)
+    })?;
+
+    // The user hook is kept and still receives only line events
+    lua.set_max_call_depth(50);
+    let err = rec.call::<i64>(100).unwrap_err();
+    assert!(
+        err.to_string().contains("out of Lua stack"),
+        "unexpected error: {err}"
+    );
+    assert!(lines.load(Ordering::Relaxed) > 0);
+
+    // Setting the hook again keeps the limit
+    let lines2 = lines.clone();
+    lua.set_hook(HookTriggers::EVERY_LINE, move |_, debug| {
+        assert_eq!(debug.event(), DebugEvent::Line);
+        lines2.fetch_add(1, Ordering::Relaxed);
+        Ok(VmState::Continue)
+    })?;
+    assert!(rec.call::<i64>(100).is_err());
+
+    // Removing the limit restores the user hook
+    lua.remove_max_call_depth();
+    lines.store(0, Ordering::Relaxed);
+    assert_eq!(rec.call::<i64>(100)?, 100);
+    assert!(lines.load(Ordering::Relaxed) > 0);
+
+    // Removing the user hook keeps the limit
+    lua.set_max_call_depth(50);
+    lua.remove_hook();
+    lines.store(0, Ordering::Relaxed);
+    assert!(rec.call::<i64>(100).is_err());
+    assert_eq!(lines.load(Ordering::Relaxed), 0);
+
+    Ok(())
+}
@@ -197,7 +197,9 @@ fn test_readonly_table() -> Result<()> {
 fn test_sandbox() -> Result<()> {
     let lua = Lua::new();
 
+    assert!(!lua.is_sandboxed());
     lua.sandbox(true)?;
+    assert!(lua.is_sandboxed());
 
     lua.load("global = 123").exec()?;
     let n: i32 = lua.load("return global").eval()?;
@@ -223,6 +225,7 @@ fn test_sandbox() -> Result<()> {
     assert!(collectgarbage.call::<u64>("count").unwrap() > 0);
 
     lua.sandbox(false)?;
+    assert!(!lua.is_sandboxed());
 
     // Previously set variable `global` should be cleared now
     assert_eq!(lua.globals().get::<Option<i32>>("global")?, None);
@@ -294,6 +297,100 @@ fn test_sandbox_threads() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_thread_set_environment() -> Result<()> {
+    let lua = Lua::new();
+
+    let f = lua.create_function(|lua, v: Value| {
+        lua.globals().set("global", v)?;
+        lua.globals().get::<Value>("marker")
+    })?;
+    lua.globals().set("marker", "main")?;
+
+    let env = lua.create_table()?;
+    env.set("marker", "env")?;
+    let co = lua.create_thread(f.clone())?;
+    co.set_environment(env.clone())?;
+    assert_eq!(co.resume::<String>(123)?, "env");
+    assert_eq!(env.get::<i32>("global")?, 123);
+    assert_eq!(lua.globals().get::<Option<i32>>("global")?, None);
+
+    // Resetting the thread restores the main globals
+    co.reset(f)?;
+    assert_eq!(co.resume::<String>(321)?, "main");
+    assert_eq!(lua.globals().get::<Option<i32>>("global")?, Some(321));
+
+    Ok(())
+}
+
+#[test]
+fn test_analyze_requires() -> Result<()> {
+    let lua = Lua::new();
+
+    let source = r#"
+        local utils = require("./utils")
+        local json = require "@lune/json" -- require("./unused")
+        local dynamic = require(utils.path)
+        return { utils = utils, json = json, dynamic = dynamic }
+    "#;
+    assert_eq!(lua.analyze_requires(source)?, ["./utils", "@lune/json"]);
+
+    // Nothing is executed
+    assert_eq!(lua.analyze_requires("error('boom') require('./a')")?, ["./a"]);
+
+    // Invalid source is rejected
+    assert!(matches!(
+        lua.analyze_requires("require("),
+        Err(Error::SyntaxError { .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_create_function_auto() -> Result<()> {
+    let lua = Lua::new();
+
+    fn sum(_: &Lua, (a, b): (i64, i64)) -> Result<i64> {
+        Ok(a + b)
+    }
+
+    let get_name = lua.load("return debug.info(..., 'n')").into_function()?;
+    let f = lua.create_function_auto(sum)?;
+    assert_eq!(f.call::<i64>((1, 2))?, 3);
+    assert_eq!(get_name.call::<String>(&f)?, "sum");
+
+    let f = lua.create_function_auto(|_, ()| Ok(()))?;
+    assert_eq!(get_name.call::<String>(&f)?, "test_create_function_auto");
+
+    Ok(())
+}
+
+#[test]
+fn test_max_call_depth() -> Result<()> {
+    let lua = Lua::new();
+
+    lua.set_max_call_depth(50);
+    let rec = lua
+        .load("local function f(n) if n == 0 then return 0 end return 1 + f(n - 1) end return f")
+        .eval::<Function>()?;
+    assert_eq!(rec.call::<i64>(10)?, 10);
+    let err = rec.call::<i64>(100).unwrap_err();
+    assert!(
+        err.to_string().contains("out of Lua stack"),
+        "unexpected error: {err}"
+    );
+
+    // Interrupt callback still works together with the limit
+    lua.set_interrupt(|_| Ok(VmState::Continue));
+    assert!(rec.call::<i64>(100).is_err());
+
+    lua.remove_max_call_depth();
+    assert_eq!(rec.call::<i64>(100)?, 100);
+
+    Ok(())
+}
+
 #[test]
 fn test_interrupts() -> Result<()> {
     let lua = Lua::new();
@@ -591,3 +688,85 @@ fn test_try_call() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_function_bytecode() -> Result<()> {
+    let lua = Lua::new();
+
+    // Functions compiled by Luau internally don't retain bytecode
+    let func = lua
+        .load("return function() end")
+        .set_retain_bytecode(true)
+        .into_function()?;
+    assert!(func.bytecode().is_none());
+    assert!(func.call::<Function>(())?.bytecode().is_none());
+
+    // Bytecode is not retained unless requested
+    let bytecode = Compiler::new().compile("return 1 + 2")?;
+    let func = lua
+        .load("return 1 + 2")
+        .set_compiler(Compiler::new())
+        .into_function()?;
+    assert!(func.bytecode().is_none());
+
+    // Functions loaded from precompiled chunks retain it when requested
+    let func = lua
+        .load("return 1 + 2")
+        .set_compiler(Compiler::new())
+        .set_retain_bytecode(true)
+        .into_function()?;
+    assert_eq!(func.bytecode().as_deref(), Some(bytecode.as_slice()));
+
+    // Bytecode can be loaded back
+    let func2 = lua
+        .load(func.bytecode().unwrap())
+        .set_retain_bytecode(true)
+        .into_function()?;
+    assert_eq!(func2.call::<i32>(())?, 3);
+    assert_eq!(func2.bytecode(), func.bytecode());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "luau-jit")]
+fn test_jit_toggle() -> Result<()> {
+    let lua = Lua::new();
+    assert!(lua.is_jit_enabled());
+
+    let jit_fn = lua
+        .load("return function(x) return x * 2 end")
+        .eval::<Function>()?;
+    lua.enable_jit(false);
+    assert!(!lua.is_jit_enabled());
+
+    // Functions loaded before the toggle keep working, new ones are interpreted
+    let interp_fn = lua
+        .load("return function(x) return x * 3 end")
+        .eval::<Function>()?;
+    assert_eq!(jit_fn.call::<i64>(21)?, 42);
+    assert_eq!(interp_fn.call::<i64>(2)?, 6);
+
+    lua.enable_jit(true);
+    assert!(lua.is_jit_enabled());
+
+    Ok(())
+}
+
+#[test]
+fn test_current_line_atomic() -> Result<()> {
+    let lua = Lua::new();
+    assert_eq!(lua.current_line_atomic(), None);
+
+    lua.set_interrupt(|_| Ok(VmState::Continue));
+    lua.load("local n = 0\nfor i = 1, 10 do\n  n += i\nend").exec()?;
+    // Interrupts do not publish the line unless asked to
+    assert_eq!(lua.current_line_atomic(), None);
+
+    lua.track_interrupt_line(true);
+    lua.load("local n = 0\nfor i = 1, 10 do\n  n += i\nend").exec()?;
+    let line = lua.current_line_atomic().expect("line must be published");
+    assert!((1..=4).contains(&line));
+
+    Ok(())
+}
@@ -87,6 +87,12 @@ fn test_gc_control() -> Result<()> {
         assert!(lua.gc_is_running());
     }
 
+    assert!(lua.gc_is_enabled());
+    lua.gc_stop();
+    assert!(!lua.gc_is_enabled());
+    lua.gc_restart();
+    assert!(lua.gc_is_enabled());
+
     assert_eq!(lua.gc_inc(200, 100, 13), GCMode::Incremental);
 
     struct MyUserdata(#[allow(unused)] Arc<()>);
@@ -131,3 +137,97 @@ fn test_gc_error() {
         Ok(()) => panic!("__gc error did not result in error"),
     }
 }
+
+#[test]
+fn test_gc_count_bytes() -> Result<()> {
+    let lua = Lua::new();
+
+    let initial = lua.gc_count_bytes();
+    assert!(initial > 0);
+
+    lua.gc_stop();
+    let _t = lua.create_sequence_from(0..10000)?;
+    assert!(lua.gc_count_bytes() > initial);
+
+    Ok(())
+}
+
+#[test]
+fn test_allocation_count() -> Result<()> {
+    let lua = Lua::new();
+    lua.gc_stop();
+
+    let initial = lua.allocation_count()?;
+    assert!(initial > 0);
+
+    // Reading state should not allocate
+    let _ = lua.used_memory();
+    assert_eq!(lua.allocation_count()?, initial);
+
+    let _t = lua.create_table()?;
+    assert!(lua.allocation_count()? > initial);
+
+    Ok(())
+}
+
+#[test]
+fn test_gc_collect_measured() -> Result<()> {
+    let lua = Lua::new();
+    lua.gc_collect()?;
+    lua.gc_collect()?;
+
+    lua.gc_stop();
+    drop(lua.create_sequence_from(0..10000)?);
+    let reclaimed = lua.gc_collect_measured()?;
+    assert!(reclaimed > 10000, "reclaimed only {reclaimed} bytes");
+
+    Ok(())
+}
+
+#[test]
+fn test_try_gc_collect() -> Result<()> {
+    let lua = Lua::new();
+
+    lua.try_gc_collect()?;
+    let collect = lua.create_function(|lua, ()| lua.try_gc_collect())?;
+    collect.call::<()>(())?;
+
+    #[cfg(feature = "luau")]
+    {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        lua.set_interrupt(|_| Ok(mluau::VmState::Continue));
+        let refused = Arc::new(AtomicBool::new(false));
+        let refused2 = refused.clone();
+        lua.set_gc_interrupt(move |lua, _| {
+            if lua.try_gc_collect().is_err() {
+                refused2.store(true, Ordering::Relaxed);
+            }
+        });
+        lua.load("local t = {} for i = 1, 1e4 do t[i] = {} end").exec()?;
+        lua.gc_collect()?;
+        assert!(refused.load(Ordering::Relaxed));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_gc_collect_incremental() -> Result<()> {
+    let lua = Lua::new();
+
+    lua.load("for i = 1, 10000 do local t = {i} end").exec()?;
+
+    // Suspended after the first step
+    let mut steps = 0;
+    let finished = lua.gc_collect_incremental(1, || {
+        steps += 1;
+        false
+    })?;
+    assert!(finished || steps == 1);
+
+    // Runs until the cycle is finished
+    assert!(lua.gc_collect_incremental(1, || true)?);
+
+    Ok(())
+}
@@ -1,4 +1,7 @@
-use mluau::{Error, ExternalError, Integer, IntoLuaMulti, Lua, MultiValue, Result, String, Value, Variadic};
+use mluau::{
+    Error, ExternalError, ExternalResult, Integer, IntoLuaMulti, Lua, LuaResultTuple, MultiValue, Result,
+    String, Value, Variadic,
+};
 
 #[test]
 fn test_result_conversions() -> Result<()> {
@@ -59,6 +62,40 @@ fn test_result_conversions() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_result_tuple() -> Result<()> {
+    let lua = Lua::new();
+
+    let parse = lua.create_function(|_, (s, raise): (String, bool)| {
+        let res = s.to_str()?.parse::<i64>().into_lua_err();
+        if raise {
+            return Ok(LuaResultTuple(Ok(res?)));
+        }
+        Ok(LuaResultTuple(res))
+    })?;
+    lua.globals().set("parse", parse)?;
+
+    lua.load(
+        r#"
+        assert(parse("42", false) == 42)
+
+        local r, e = parse("abc", false)
+        assert(r == nil)
+        assert(type(e) == "string" and e:find("invalid digit") ~= nil)
+
+        assert(not pcall(parse, "abc", true))
+    "#,
+    )
+    .exec()?;
+
+    let multi = LuaResultTuple::<i64>(Err("failure".into_lua_err())).into_lua_multi(&lua)?;
+    assert_eq!(multi.len(), 2);
+    assert_eq!(multi[0], Value::Nil);
+    assert_eq!(multi[1].as_string().unwrap(), "failure");
+
+    Ok(())
+}
+
 #[test]
 fn test_multivalue() {
     let mut multi = MultiValue::with_capacity(3);
@@ -67,9 +104,17 @@ fn test_multivalue() {
     multi.push_front(Value::Integer(3));
     assert_eq!(multi.iter().filter_map(|v| v.as_integer()).sum::<Integer>(), 6);
 
+    assert_eq!(multi.get(1), Some(&Value::Integer(1)));
+    assert_eq!(multi.get(3), None);
+
     let vec = multi.into_vec();
     assert_eq!(&vec, &[Value::Integer(3), Value::Integer(1), Value::Integer(2)]);
-    let _multi2 = MultiValue::from_vec(vec);
+    let multi2 = MultiValue::from_vec(vec);
+
+    let (first, rest) = multi2.split_first().unwrap();
+    assert_eq!(first, Value::Integer(3));
+    assert_eq!(rest.into_vec(), vec![Value::Integer(1), Value::Integer(2)]);
+    assert!(MultiValue::new().split_first().is_none());
 }
 
 #[test]
@@ -338,6 +338,35 @@ fn test_to_value_struct() -> LuaResult<()> {
     .exec()
 }
 
+#[test]
+fn test_create_table_from_serialize() -> LuaResult<()> {
+    let lua = Lua::new();
+
+    #[derive(Serialize)]
+    struct Config {
+        name: String,
+        workers: u32,
+    }
+
+    let config = Config {
+        name: "app".to_string(),
+        workers: 4,
+    };
+    let t = lua.create_table_from_serialize(&config)?;
+    assert_eq!(t.get::<String>("name")?, "app");
+    assert_eq!(t.get::<u32>("workers")?, 4);
+
+    let t = lua.create_table_from_serialize(&[1, 2, 3])?;
+    assert_eq!(t.raw_len(), 3);
+
+    match lua.create_table_from_serialize(&123) {
+        Err(Error::SerializeError(msg)) => assert!(msg.contains("got number")),
+        r => panic!("expected SerializeError, got {r:?}"),
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_to_value_enum() -> LuaResult<()> {
     let lua = Lua::new();
@@ -839,3 +868,39 @@ fn test_buffer_from_value() -> LuaResult<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "luau")]
+#[test]
+fn test_buffer_as_base64() -> LuaResult<()> {
+    let lua = Lua::new();
+
+    let buf = Value::Buffer(lua.create_buffer(b"hello")?);
+    let json = serde_json::to_value(buf.to_serializable().buffers_as_base64(true)).unwrap();
+    assert_eq!(json, serde_json::json!("aGVsbG8="));
+
+    let options = DeserializeOptions::new().buffers_as_base64(true);
+    let val = lua.from_value_with::<String>(buf, options)?;
+    assert_eq!(val, "aGVsbG8=");
+
+    let buf = Value::Buffer(lua.create_buffer(b"")?);
+    let json = serde_json::to_value(buf.to_serializable().buffers_as_base64(true)).unwrap();
+    assert_eq!(json, serde_json::json!(""));
+
+    Ok(())
+}
+
+#[test]
+fn test_null_array_roundtrip() -> Result<(), Box<dyn StdError>> {
+    let lua = Lua::new();
+
+    let json: serde_json::Value = serde_json::from_str("[1, null, 3]")?;
+    let value = lua.to_value(&json)?;
+    let table = value.as_table().unwrap();
+    assert_eq!(table.raw_len(), 3);
+    assert_eq!(table.raw_get::<Value>(2)?, lua.null());
+
+    let json2 = lua.from_value::<serde_json::Value>(value)?;
+    assert_eq!(json2, json);
+
+    Ok(())
+}
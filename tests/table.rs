@@ -113,6 +113,24 @@ fn test_table_push_pop() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_table_extend() -> Result<()> {
+    let lua = Lua::new();
+
+    let table = lua.create_sequence_from([1, 2])?;
+    table.extend([3, 4, 5])?;
+    assert_eq!(table, [1, 2, 3, 4, 5]);
+
+    table.extend(Vec::<i32>::new())?;
+    assert_eq!(table.raw_len(), 5);
+
+    let table = lua.create_table()?;
+    table.extend((1..=3).map(|i| format!("v{i}")))?;
+    assert_eq!(table, ["v1", "v2", "v3"]);
+
+    Ok(())
+}
+
 #[test]
 fn test_table_insert_remove() -> Result<()> {
     let lua = Lua::new();
@@ -289,6 +307,31 @@ fn test_table_for_each_value() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_table_map_values() -> Result<()> {
+    let lua = Lua::new();
+
+    let table = lua.load("{1, 2, 3, a = 10, skip = 'x'}").eval::<Table>()?;
+    let mapped = table.map_values(|v| match v {
+        Value::Integer(i) => Ok(Value::Integer(i * 2)),
+        Value::Number(n) => Ok(Value::Number(n * 2.0)),
+        _ => Ok(Value::Nil),
+    })?;
+    assert_eq!(mapped.raw_len(), 3);
+    assert_eq!(mapped.get::<i64>(1)?, 2);
+    assert_eq!(mapped.get::<i64>(3)?, 6);
+    assert_eq!(mapped.get::<i64>("a")?, 20);
+    assert!(!mapped.contains_key("skip")?);
+    // The source table is not modified
+    assert_eq!(table.get::<i64>("a")?, 10);
+
+    // Errors are propagated
+    let res = table.map_values(|_| Err(Error::runtime("map error")));
+    assert!(matches!(res, Err(Error::RuntimeError(msg)) if msg == "map error"));
+
+    Ok(())
+}
+
 #[test]
 fn test_table_scope() -> Result<()> {
     let lua = Lua::new();
@@ -324,10 +367,18 @@ fn test_metatable() -> Result<()> {
     let table = lua.create_table()?;
     let metatable = lua.create_table()?;
     metatable.set("__index", lua.create_function(|_, ()| Ok("index_value"))?)?;
-    table.set_metatable(Some(metatable))?;
+    assert!(table.set_metatable(Some(metatable.clone()))?.is_none());
     assert_eq!(table.get::<String>("any_key")?, "index_value");
     assert_eq!(table.raw_get::<Value>("any_key")?, Value::Nil);
-    table.set_metatable(None)?;
+
+    // Swap in a temporary metatable and restore the previous one
+    let prev = table.set_metatable(Some(lua.create_table()?))?;
+    assert_eq!(prev.as_ref(), Some(&metatable));
+    assert_eq!(table.get::<Value>("any_key")?, Value::Nil);
+    table.set_metatable(prev)?;
+    assert_eq!(table.get::<String>("any_key")?, "index_value");
+
+    assert_eq!(table.set_metatable(None)?, Some(metatable));
     assert_eq!(table.get::<Value>("any_key")?, Value::Nil);
 
     Ok(())
@@ -604,3 +655,83 @@ fn test_table_get_path() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_try_create_sequence_from() -> Result<()> {
+    let lua = Lua::new();
+
+    // Spans several batches and has no useful size hint
+    let iter = (1..=5000).filter(|i| i % 2 == 0).map(Ok);
+    let table = lua.try_create_sequence_from(iter)?;
+    assert_eq!(table.raw_len(), 2500);
+    assert_eq!(table.raw_get::<i64>(1)?, 2);
+    assert_eq!(table.raw_get::<i64>(2500)?, 5000);
+
+    let empty = lua.try_create_sequence_from(std::iter::empty::<Result<i64>>())?;
+    assert_eq!(empty.raw_len(), 0);
+
+    let iter = (1..10).map(|i| {
+        if i < 5 {
+            Ok(i)
+        } else {
+            Err(Error::runtime("stream failed"))
+        }
+    });
+    match lua.try_create_sequence_from(iter) {
+        Err(Error::RuntimeError(msg)) => assert_eq!(msg, "stream failed"),
+        r => panic!("expected RuntimeError, got {r:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_table_describe() -> Result<()> {
+    let lua = Lua::new();
+
+    let t = lua
+        .load("return {10, 20, 30, x = 1, [true] = 2, [5] = 3}")
+        .eval::<Table>()?;
+    let shape = t.describe();
+    assert_eq!(shape.array_len, 3);
+    assert_eq!(shape.hash_len, 3);
+    assert_eq!(shape.key_types, vec!["boolean", "number", "string"]);
+    assert!(!shape.has_metatable);
+
+    // Metamethods are not invoked
+    let t = lua.create_table()?;
+    let mt = lua
+        .load("return {__index = function() error('called') end}")
+        .eval::<Table>()?;
+    t.set_metatable(Some(mt))?;
+    let shape = t.describe();
+    assert_eq!((shape.array_len, shape.hash_len), (0, 0));
+    assert!(shape.key_types.is_empty());
+    assert!(shape.has_metatable);
+
+    Ok(())
+}
+
+#[test]
+fn test_table_keys_difference() -> Result<()> {
+    let lua = Lua::new();
+
+    let old = lua.load("{a = 1, b = 2, 10}").eval::<Table>()?;
+    let new = lua.load("{b = 3, c = 4, 10}").eval::<Table>()?;
+
+    let mut keys = old
+        .keys()?
+        .into_iter()
+        .map(|k| k.to_string())
+        .collect::<Result<Vec<_>>>()?;
+    keys.sort();
+    assert_eq!(keys, ["1", "a", "b"]);
+
+    let removed = old.difference_keys(&new)?;
+    assert_eq!(removed, [Value::String(lua.create_string("a")?)]);
+    let added = new.difference_keys(&old)?;
+    assert_eq!(added, [Value::String(lua.create_string("c")?)]);
+    assert!(old.difference_keys(&old)?.is_empty());
+
+    Ok(())
+}
@@ -4,12 +4,12 @@ use std::iter::FromIterator;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::string::String as StdString;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::{error, f32, f64, fmt};
 
 use mluau::{
-    ffi, ChunkMode, Error, ExternalError, Function, Lua, LuaOptions, Nil, Result, StdLib, String, Table,
-    UserData, Value, Variadic,
+    ffi, ChunkMode, Error, ExternalError, Function, Lua, LuaBackend, LuaOptions, Nil, Result, StdLib, String,
+    Table, UserData, Value, Variadic,
 };
 
 #[test]
@@ -84,6 +84,45 @@ fn test_safety() -> Result<()> {
     Ok(())
 }
 
+#[cfg(not(feature = "luau"))]
+#[test]
+fn test_c_module_loader() -> Result<()> {
+    let lua = Lua::new();
+
+    lua.set_c_module_loader(|lua, name| match name {
+        "native" => Ok(Some(lua.create_function(|_, ()| Ok("native module"))?)),
+        _ => Ok(None),
+    })?;
+    assert_eq!(
+        lua.load(r#"require "native""#).eval::<StdString>()?,
+        "native module"
+    );
+
+    match lua.load(r#"require "fake_ffi""#).exec() {
+        Err(Error::RuntimeError(msg)) => {
+            assert!(msg.contains("no native module 'fake_ffi'"));
+            assert!(msg.contains("can't load C modules in safe mode"));
+        }
+        r => panic!("expected RuntimeError, got {r:?}"),
+    }
+
+    // Replace the loader
+    lua.set_c_module_loader(|lua, name| match name {
+        "native2" => Ok(Some(lua.create_function(|_, ()| Ok("native module 2"))?)),
+        _ => Ok(None),
+    })?;
+    assert_eq!(
+        lua.load(r#"require "native2""#).eval::<StdString>()?,
+        "native module 2"
+    );
+    assert!(lua
+        .load(r#"package.loaded.native = nil; require "native""#)
+        .exec()
+        .is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_load() -> Result<()> {
     let lua = Lua::new();
@@ -155,9 +194,24 @@ fn test_replace_globals() -> Result<()> {
     let globals = lua.create_table()?;
     globals.set("foo", "bar")?;
 
+    let old_globals = lua.globals();
+    assert_eq!(lua.globals(), old_globals);
+
     lua.set_globals(globals.clone())?;
     let val = lua.load("return foo").eval::<StdString>()?;
     assert_eq!(val, "bar");
+    assert_eq!(lua.globals(), globals);
+    assert_ne!(lua.globals(), old_globals);
+
+    // Sandboxing replaces globals table
+    #[cfg(feature = "luau")]
+    {
+        lua.sandbox(true)?;
+        assert_ne!(lua.globals(), globals);
+        assert_eq!(lua.globals().get::<StdString>("foo")?, "bar");
+        lua.sandbox(false)?;
+        assert_eq!(lua.globals(), old_globals);
+    }
 
     // Updating globals in sandboxed Lua state is not allowed
     #[cfg(feature = "luau")]
@@ -173,6 +227,30 @@ fn test_replace_globals() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_with_global() -> Result<()> {
+    let lua = Lua::new();
+    lua.globals().set("x", 1)?;
+
+    let res = lua.with_global("x", 2, || lua.load("return x").eval::<i64>())?;
+    assert_eq!(res, 2);
+    assert_eq!(lua.globals().get::<i64>("x")?, 1);
+
+    // Restored on error
+    let res = lua.with_global("y", "temp", || lua.load("error(y)").exec());
+    assert!(res.is_err());
+    assert_eq!(lua.globals().get::<Value>("y")?, Value::Nil);
+
+    // Restored on panic
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        lua.with_global("x", 3, || -> Result<()> { panic!("boom") })
+    }));
+    assert!(res.is_err());
+    assert_eq!(lua.globals().get::<i64>("x")?, 1);
+
+    Ok(())
+}
+
 #[test]
 fn test_load_mode() -> Result<()> {
     let lua = unsafe { Lua::unsafe_new() };
@@ -382,6 +460,12 @@ fn test_error() -> Result<()> {
         Err(_) => panic!("error is not LuaSyntaxError::IncompleteStatement kind"),
         _ => panic!("error not returned"),
     }
+    match lua.load("local = 1").set_name("@bad_file.lua").exec() {
+        Err(Error::SyntaxError { chunk_name, .. }) => {
+            assert_eq!(chunk_name.as_deref(), Some("@bad_file.lua"))
+        }
+        r => panic!("expected SyntaxError, got {r:?}"),
+    }
 
     let test_pcall = globals.get::<Function>("test_pcall")?;
     test_pcall.call::<()>(())?;
@@ -528,6 +612,38 @@ fn test_panic() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_panic_handler() -> Result<()> {
+    let lua = Lua::new();
+
+    let caught = Arc::new(std::sync::Mutex::new(None));
+    let caught2 = caught.clone();
+    lua.set_panic_handler(move |lua, payload| {
+        let msg = payload.downcast_ref::<&str>().copied().unwrap_or_default();
+        let traceback = lua.traceback(None, 0).unwrap();
+        *caught2.lock().unwrap() = Some((msg.to_string(), traceback));
+    });
+
+    let rust_panic_function = lua.create_function(|_, ()| -> Result<()> { panic!("rust panic") })?;
+    lua.globals().set("rust_panic_function", rust_panic_function)?;
+
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        lua.load("local function inner() rust_panic_function() end inner()")
+            .exec()
+    }));
+    assert!(res.is_err());
+    let (msg, traceback) = caught.lock().unwrap().take().unwrap();
+    assert_eq!(msg, "rust panic");
+    assert!(traceback.contains("inner"));
+
+    lua.remove_panic_handler();
+    let res = catch_unwind(AssertUnwindSafe(|| lua.load("rust_panic_function()").exec()));
+    assert!(res.is_err());
+    assert!(caught.lock().unwrap().is_none());
+
+    Ok(())
+}
+
 #[cfg(target_pointer_width = "64")]
 #[test]
 fn test_safe_integers() -> Result<()> {
@@ -581,6 +697,13 @@ fn test_num_conversion() -> Result<()> {
         Some(1.5)
     );
 
+    assert_eq!(lua.string_to_number("  0x10  ")?, Some(Value::Number(16.0)));
+    assert_eq!(lua.string_to_number("1e2")?, Some(Value::Number(100.0)));
+    assert_eq!(lua.string_to_number("1.5abc")?, None);
+    assert_eq!(lua.string_to_number("")?, None);
+    #[cfg(any(feature = "lua54", feature = "lua53"))]
+    assert!(matches!(lua.string_to_number("42")?, Some(Value::Integer(42))));
+
     assert_eq!(lua.load("1.0").eval::<i64>()?, 1);
     assert_eq!(lua.load("1.0").eval::<f64>()?, 1.0);
     #[cfg(any(feature = "lua54", feature = "lua53"))]
@@ -899,6 +1022,31 @@ fn test_registry_value_reuse() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_light_ref() -> Result<()> {
+    let lua = Lua::new();
+
+    let t = lua.create_table()?;
+    t.set("x", 1)?;
+    let r = lua.create_light_ref(&Value::Table(t))?;
+    assert_eq!(lua.light_ref_value::<Table>(&r)?.get::<i32>("x")?, 1);
+
+    let nil = lua.create_light_ref(&Value::Nil)?;
+    assert_eq!(nil.id(), ffi::LUA_REFNIL);
+    assert_eq!(lua.light_ref_value::<Value>(&nil)?, Value::Nil);
+
+    // Released slots are reused
+    let id = r.id();
+    lua.drop_light_ref(r);
+    let r2 = lua.create_light_ref(&Value::Integer(5))?;
+    assert_eq!(r2.id(), id);
+    assert_eq!(lua.light_ref_value::<i64>(&r2)?, 5);
+    lua.drop_light_ref(r2);
+    lua.drop_light_ref(nil);
+
+    Ok(())
+}
+
 #[test]
 #[cfg(not(panic = "abort"))]
 fn test_application_data() -> Result<()> {
@@ -1655,6 +1803,7 @@ fn test_gc_drop_ref_thread() -> Result<()> {
 fn test_get_or_init_from_ptr() -> Result<()> {
     // This would not work with Luau, the state must be init by mlua internally
     let state = unsafe { ffi::luaL_newstate() };
+    assert!(unsafe { Lua::try_from_ptr(state) }.is_none());
 
     let mut lua = unsafe { Lua::get_or_init_from_ptr(state) };
     lua.globals().set("hello", "world678")?;
@@ -1662,6 +1811,8 @@ fn test_get_or_init_from_ptr() -> Result<()> {
     // The same Lua instance must be returned
     lua = unsafe { Lua::get_or_init_from_ptr(state) };
     assert_eq!(lua.globals().get::<String>("hello")?, "world678");
+    let found = unsafe { Lua::try_from_ptr(state) }.expect("Lua must be initialized");
+    assert_eq!(found.globals().get::<String>("hello")?, "world678");
 
     unsafe { ffi::lua_close(state) };
 
@@ -1690,3 +1841,83 @@ fn test_onclose() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_backend() -> Result<()> {
+    let lua = Lua::new();
+
+    #[cfg(feature = "luau")]
+    assert_eq!(lua.backend(), LuaBackend::Luau);
+    #[cfg(feature = "luajit")]
+    assert_eq!(lua.backend(), LuaBackend::LuaJit);
+    #[cfg(feature = "lua54")]
+    assert_eq!(lua.backend(), LuaBackend::Lua54);
+    #[cfg(feature = "lua51")]
+    assert_eq!(lua.backend(), LuaBackend::Lua51);
+
+    // LuaJIT reports itself as "Lua 5.1" in `_VERSION`
+    #[cfg(not(feature = "luajit"))]
+    assert_eq!(lua.version_string(), lua.globals().get::<StdString>("_VERSION")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_ref_thread_stats() -> Result<()> {
+    let lua = Lua::new();
+
+    let used = |lua: &Lua| {
+        let stats = lua.ref_thread_stats();
+        assert!(stats.aux_threads >= 1);
+        assert!(stats.free_slots <= stats.total_slots);
+        stats.total_slots - stats.free_slots
+    };
+
+    let before = used(&lua);
+    let tables = (0..100).map(|_| lua.create_table()).collect::<Result<Vec<_>>>()?;
+    assert_eq!(used(&lua), before + 100);
+    drop(tables);
+    assert_eq!(used(&lua), before);
+
+    Ok(())
+}
+
+#[test]
+fn test_lua_name() {
+    let lua = Lua::new();
+    assert_eq!(lua.name(), None);
+    assert!(format!("{lua:?}").starts_with("Lua(0x"));
+
+    lua.set_name("tenant-1");
+    assert_eq!(lua.name().as_deref(), Some("tenant-1"));
+    assert!(format!("{lua:?}").starts_with("Lua(\"tenant-1\", 0x"));
+}
+
+#[test]
+fn test_set_print() -> Result<()> {
+    let lua = Lua::new();
+
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let output2 = output.clone();
+    lua.set_print(move |_, line| {
+        output2.lock().unwrap().push(line.to_string());
+        Ok(())
+    })?;
+
+    lua.load(
+        r#"
+        local t = setmetatable({}, { __tostring = function() return "custom" end })
+        print("a", 1, nil, true, t)
+        print()
+    "#,
+    )
+    .exec()?;
+    assert_eq!(*output.lock().unwrap(), ["a\t1\tnil\ttrue\tcustom", ""]);
+
+    // Errors from the callback are propagated to the script
+    lua.set_print(|_, _| Err(Error::runtime("print failed")))?;
+    let err = lua.load("print('x')").exec().unwrap_err();
+    assert!(err.to_string().contains("print failed"));
+
+    Ok(())
+}
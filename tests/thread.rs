@@ -1,6 +1,6 @@
 use std::panic::catch_unwind;
 
-use mluau::{Error, Function, IntoLua, Lua, Result, Thread, ThreadStatus, Value};
+use mluau::{Error, ErrorContext, Function, IntoLua, Lua, Result, Thread, ThreadStatus, Value};
 
 #[test]
 fn test_thread() -> Result<()> {
@@ -937,3 +937,197 @@ pub fn test_thread_set_thread_data() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_exec_isolated() -> Result<()> {
+    let lua = Lua::new();
+
+    let sum = lua.exec_isolated::<i64>(lua.load("return 1 + 2"))?;
+    assert_eq!(sum, 3);
+
+    // Yields are resumed until the chunk finishes
+    let chunk = lua.load(
+        r#"
+        local n = 0
+        for i = 1, 3 do
+            coroutine.yield(i)
+            n = n + i
+        end
+        return n
+    "#,
+    );
+    assert_eq!(lua.exec_isolated::<i64>(chunk)?, 6);
+
+    assert!(lua.exec_isolated::<()>(lua.load("error('boom')")).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_thread_pool() -> Result<()> {
+    let lua = Lua::new();
+
+    let func = lua.create_function(|_, x: i64| Ok(x * 2))?;
+    let thread = lua.acquire_thread(func.clone())?;
+    let ptr = thread.to_pointer();
+    assert_eq!(thread.resume::<i64>(21)?, 42);
+    assert!(lua.release_thread(thread));
+
+    // The released thread is reused with the new body
+    let thread = lua.acquire_thread(lua.load("return ...").into_function()?)?;
+    assert_eq!(thread.to_pointer(), ptr);
+    assert_eq!(thread.status(), ThreadStatus::Resumable);
+    assert_eq!(thread.resume::<String>("hello")?, "hello");
+
+    // Threads with other handles are not recycled
+    let thread2 = thread.clone();
+    assert!(!lua.release_thread(thread));
+    assert_eq!(thread2.status(), ThreadStatus::Finished);
+
+    // Threads not acquired from the pool or passed to Lua are not recycled
+    assert!(!lua.release_thread(lua.create_thread(func.clone())?));
+    let thread = lua.acquire_thread(func.clone())?;
+    lua.globals().set("t", &thread)?;
+    assert!(!lua.release_thread(thread));
+
+    // Per-thread state is cleared on release
+    let thread = lua.acquire_thread(func.clone())?;
+    #[cfg(feature = "luau")]
+    thread.set_thread_data(123i32)?;
+    #[cfg(not(feature = "luau"))]
+    thread.set_hook(mluau::HookTriggers::EVERY_LINE, |_, _| {
+        Err(Error::runtime("stale hook"))
+    })?;
+    thread.set_error_handler(|_, _| Error::runtime("stale handler"))?;
+    assert!(lua.release_thread(thread));
+    let thread = lua.acquire_thread(lua.load("error('boom')").into_function()?)?;
+    #[cfg(feature = "luau")]
+    assert!(thread.thread_data::<i32>().is_none());
+    let err = thread.resume::<()>(()).unwrap_err().to_string();
+    assert!(err.contains("boom") && !err.contains("stale"));
+    drop(thread);
+
+    // Pool is empty, so a new thread is created
+    let thread = lua.acquire_thread(func.clone())?;
+    assert_ne!(thread.to_pointer(), ptr);
+    assert_eq!(thread.resume::<i64>(1)?, 2);
+
+    // The pool size is configurable
+    lua.set_thread_pool_size(1);
+    let thread2 = lua.acquire_thread(func.clone())?;
+    thread2.resume::<i64>(1)?;
+    assert!(lua.release_thread(thread));
+    assert!(!lua.release_thread(thread2));
+
+    Ok(())
+}
+
+#[test]
+fn test_thread_resume_from_yield() -> Result<()> {
+    let lua = Lua::new();
+
+    let start = lua.create_thread(lua.load("coroutine.yield()").into_function()?)?;
+    let producer = lua.create_thread(
+        lua.load(
+            r#"
+            coroutine.yield(1, 2)
+            coroutine.yield(3, 4)
+        "#,
+        )
+        .into_function()?,
+    )?;
+    let doubler = lua.create_thread(
+        lua.load(
+            r#"
+            local a, b = ...
+            while true do
+                a, b = coroutine.yield(a * 2, b * 2)
+            end
+        "#,
+        )
+        .into_function()?,
+    )?;
+
+    // Source must be suspended in a yield
+    assert!(producer.resume_from_yield(&start).is_err());
+
+    start.resume::<()>(())?;
+    assert_eq!(producer.resume_from_yield(&start)?, (ThreadStatus::Resumable, 2));
+    assert_eq!(
+        doubler.resume_from_yield(&producer)?,
+        (ThreadStatus::Resumable, 2)
+    );
+    assert_eq!(doubler.pop_results::<(i64, i64)>()?, (2, 4));
+
+    assert_eq!(producer.resume_from_yield(&start)?, (ThreadStatus::Resumable, 2));
+    assert_eq!(
+        doubler.resume_from_yield(&producer)?,
+        (ThreadStatus::Resumable, 2)
+    );
+    assert_eq!(doubler.pop_results::<(i64, i64)>()?, (6, 8));
+
+    // Source must belong to the same Lua instance
+    let lua2 = Lua::new();
+    let other = lua2.create_thread(lua2.load("coroutine.yield(1)").into_function()?)?;
+    other.resume::<()>(())?;
+    let err = doubler.resume_from_yield(&other).unwrap_err();
+    assert!(err.to_string().contains("different Lua instance"), "{err}");
+
+    Ok(())
+}
+
+#[test]
+fn test_thread_error_handler() -> Result<()> {
+    let lua = Lua::new();
+
+    let func = lua
+        .load("return function(fail) if fail then error('boom') end end")
+        .eval::<Function>()?;
+    let thread = lua.create_thread(func.clone())?;
+    thread.set_error_handler(|_, err| err.context("task 42"))?;
+    match thread.resume::<()>(true) {
+        Err(Error::WithContext { context, cause }) => {
+            assert_eq!(context, "task 42");
+            assert!(cause.to_string().contains("boom"));
+        }
+        r => panic!("expected WithContext error, got {r:?}"),
+    }
+
+    // Other threads are not affected
+    let thread2 = lua.create_thread(func)?;
+    match thread2.resume::<()>(true) {
+        Err(Error::RuntimeError(msg)) => assert!(msg.contains("boom")),
+        r => panic!("expected RuntimeError, got {r:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_is_main_thread() -> Result<()> {
+    let lua = Lua::new();
+    assert!(lua.is_main_thread());
+
+    let check = lua.create_function(|lua, ()| Ok(lua.is_main_thread()))?;
+    assert!(check.call::<bool>(())?);
+
+    let thread = lua.create_thread(check)?;
+    assert!(!thread.resume::<bool>(())?);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(any(feature = "lua51", feature = "lua52", feature = "luajit")))]
+fn test_is_yieldable() -> Result<()> {
+    let lua = Lua::new();
+    assert!(!lua.is_yieldable());
+
+    let check = lua.create_function(|lua, ()| Ok(lua.is_yieldable()))?;
+    assert!(!check.call::<bool>(())?);
+
+    let thread = lua.create_thread(check)?;
+    assert!(thread.resume::<bool>(())?);
+
+    Ok(())
+}
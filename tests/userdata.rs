@@ -210,6 +210,30 @@ fn test_metamethods() -> Result<()> {
     Ok(())
 }
 
+#[cfg(debug_assertions)]
+#[test]
+fn test_metamethod_results_count() -> Result<()> {
+    struct MyUserData;
+
+    impl UserData for MyUserData {
+        fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+            methods.add_meta_method(MetaMethod::Len, |_, _, ()| Ok(Variadic::<i64>::new()));
+            methods.add_meta_method(MetaMethod::ToString, |_, _, ()| Ok(Variadic::from(vec!["ud"])));
+        }
+    }
+
+    let lua = Lua::new();
+    lua.globals().set("ud", MyUserData)?;
+
+    let err = lua.load("return #ud").exec().unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("metamethod '__len' must return 1 value(s), got 0"));
+    assert_eq!(lua.load("tostring(ud)").eval::<StdString>()?, "ud");
+
+    Ok(())
+}
+
 #[cfg(feature = "lua54")]
 #[test]
 fn test_metamethod_close() -> Result<()> {
@@ -256,6 +280,36 @@ fn test_metamethod_close() -> Result<()> {
     Ok(())
 }
 
+#[cfg(feature = "lua54")]
+#[test]
+fn test_metamethod_close_error() -> Result<()> {
+    struct Guard(Option<StdString>);
+
+    impl UserData for Guard {
+        fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+            methods.add_meta_method_mut(MetaMethod::Close, |_, this, err: Option<StdString>| {
+                this.0 = Some(err.unwrap_or_else(|| "ok".into()));
+                Ok(())
+            });
+        }
+    }
+
+    let lua = Lua::new();
+    let guard = lua.create_userdata(Guard(None))?;
+
+    // The error value is passed to `__close` and then propagated
+    let f = lua
+        .load("local g <close> = ...; error('boom', 0)")
+        .into_function()?;
+    assert!(f.call::<()>(&guard).is_err());
+    assert_eq!(guard.borrow::<Guard>()?.0.as_deref(), Some("boom"));
+
+    lua.load("local g <close> = ...").call::<()>(&guard)?;
+    assert_eq!(guard.borrow::<Guard>()?.0.as_deref(), Some("ok"));
+
+    Ok(())
+}
+
 #[test]
 fn test_gc_userdata() -> Result<()> {
     struct MyUserdata {
@@ -1549,6 +1603,44 @@ fn test_userdata_dynamic() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_userdata_borrow_diagnostics() -> Result<()> {
+    let lua = Lua::new();
+
+    struct MyUd(i64);
+    impl UserData for MyUd {
+        fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+            methods.add_method("outer", |_, _, f: Function| f.call::<()>(()));
+            methods.add_method_mut("inner", |_, this, ()| {
+                this.0 += 1;
+                Ok(())
+            });
+        }
+    }
+
+    let ud = lua.create_userdata(MyUd(0))?;
+    lua.globals().set("ud", &ud)?;
+    let reenter = lua.load("ud:outer(function() ud:inner() end)").into_function()?;
+
+    let err = reenter.call::<()>(()).unwrap_err().to_string();
+    assert!(err.contains("error mutably borrowing userdata"));
+    assert!(!err.contains("already borrowed by method"));
+
+    lua.enable_borrow_diagnostics(true);
+    let err = reenter.call::<()>(()).unwrap_err().to_string();
+    assert!(err.contains("userdata is already borrowed by method 'MyUd.outer'"));
+
+    // Borrows are released after the method returns
+    lua.load("ud:inner()").exec()?;
+    assert_eq!(ud.borrow::<MyUd>()?.0, 1);
+
+    lua.enable_borrow_diagnostics(false);
+    let err = reenter.call::<()>(()).unwrap_err().to_string();
+    assert!(!err.contains("already borrowed by method"));
+
+    Ok(())
+}
+
 #[test]
 fn test_userdata_get_path() -> Result<()> {
     let lua = Lua::new();
@@ -1565,3 +1657,189 @@ fn test_userdata_get_path() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_userdata_dispatch() -> Result<()> {
+    let lua = Lua::new();
+
+    struct Circle(f64);
+    struct Square(f64);
+    struct Triangle;
+    impl UserData for Circle {}
+    impl UserData for Square {}
+    impl UserData for Triangle {}
+
+    let area = |ud: &AnyUserData| {
+        ud.dispatch()
+            .on(|c: &Circle| Ok(3.0 * c.0 * c.0))
+            .on(|s: &Square| Ok(s.0 * s.0))
+            .finish()
+    };
+    assert_eq!(area(&lua.create_userdata(Circle(1.0))?)?, 3.0);
+    assert_eq!(area(&lua.create_userdata(Square(2.0))?)?, 4.0);
+    let triangle = lua.create_userdata(Triangle)?;
+    assert!(matches!(area(&triangle), Err(Error::UserDataTypeMismatch)));
+
+    // Fallback and mutable arms
+    let square = lua.create_userdata(Square(2.0))?;
+    let res = square
+        .dispatch()
+        .on(|_: &Circle| Ok("circle"))
+        .on_mut(|s: &mut Square| {
+            s.0 = 3.0;
+            Ok("square")
+        })
+        .otherwise(|_| Ok("other"))?;
+    assert_eq!(res, "square");
+    assert_eq!(square.borrow::<Square>()?.0, 3.0);
+    assert_eq!(
+        triangle.dispatch().on(|_: &Circle| Ok(1)).otherwise(|_| Ok(0))?,
+        0
+    );
+
+    // Borrow errors are reported from the matched arm
+    let _guard = square.borrow_mut::<Square>()?;
+    assert!(matches!(
+        square.dispatch().on(|s: &Square| Ok(s.0)).finish(),
+        Err(Error::UserDataBorrowError)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_userdata_clone_method() -> Result<()> {
+    let lua = Lua::new();
+
+    #[derive(Clone)]
+    struct Point(i64, i64);
+
+    lua.register_userdata_type::<Point>(|reg| {
+        reg.add_clone_method("clone");
+        reg.add_method_mut("move", |_, this, (dx, dy): (i64, i64)| {
+            this.0 += dx;
+            this.1 += dy;
+            Ok(())
+        });
+        reg.add_field_method_get("x", |_, this| Ok(this.0));
+    })?;
+
+    let p = lua.create_any_userdata(Point(1, 2))?;
+    let p2 = lua
+        .load("local p = ...; local q = p:clone(); q:move(10, 10); return q")
+        .call::<AnyUserData>(&p)?;
+    assert_eq!(p.borrow::<Point>()?.0, 1);
+    assert_eq!(p2.borrow::<Point>()?.0, 11);
+    assert!(p2.is::<Point>());
+    assert_eq!(lua.load("local q = ...; return q.x").call::<i64>(&p2)?, 11);
+
+    Ok(())
+}
+
+#[test]
+fn test_userdata_destructor_order() -> Result<()> {
+    let dropped = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    struct Pool(Arc<std::sync::Mutex<Vec<&'static str>>>);
+    struct Conn(Arc<std::sync::Mutex<Vec<&'static str>>>);
+    impl UserData for Pool {}
+    impl UserData for Conn {}
+    impl Drop for Pool {
+        fn drop(&mut self) {
+            self.0.lock().unwrap().push("pool");
+        }
+    }
+    impl Drop for Conn {
+        fn drop(&mut self) {
+            self.0.lock().unwrap().push("conn");
+        }
+    }
+
+    let lua = Lua::new();
+    lua.set_userdata_destructor_order(&[TypeId::of::<Conn>(), TypeId::of::<Pool>()])?;
+    lua.globals().set("pool", Pool(dropped.clone()))?;
+    lua.globals().set("conn", Conn(dropped.clone()))?;
+    drop(lua);
+    assert_eq!(*dropped.lock().unwrap(), vec!["conn", "pool"]);
+
+    // Explicit finalization
+    dropped.lock().unwrap().clear();
+    let lua = Lua::new();
+    lua.set_userdata_destructor_order(&[TypeId::of::<Conn>()])?;
+    let conn = lua.create_userdata(Conn(dropped.clone()))?;
+    lua.run_finalizers_in_order(&[TypeId::of::<Conn>()])?;
+    assert_eq!(*dropped.lock().unwrap(), vec!["conn"]);
+    assert!(conn.borrow::<Conn>().is_err());
+
+    // Untracked types are rejected
+    let pool = lua.create_userdata(Pool(dropped.clone()))?;
+    let err = lua.run_finalizers_in_order(&[TypeId::of::<Pool>()]).unwrap_err();
+    assert!(err.to_string().contains("not tracked"));
+    assert!(pool.borrow::<Pool>().is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_field_method_get_set() -> Result<()> {
+    let lua = Lua::new();
+
+    struct Counter(i64);
+
+    impl UserData for Counter {
+        fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+            fields.add_field_method(
+                "value",
+                |_, this| Ok(this.0),
+                |_, this, val| {
+                    this.0 = val;
+                    Ok(())
+                },
+            );
+        }
+    }
+
+    lua.globals().set("counter", Counter(1))?;
+    lua.load(
+        r#"
+        assert(counter.value == 1)
+        counter.value = counter.value + 41
+        assert(counter.value == 42)
+    "#,
+    )
+    .exec()?;
+
+    Ok(())
+}
+
+#[test]
+fn test_userdata_debug_string() -> Result<()> {
+    let lua = Lua::new();
+
+    struct Plain;
+    impl UserData for Plain {}
+
+    struct Named(&'static str);
+    impl UserData for Named {
+        fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+            methods.add_meta_method(MetaMethod::ToString, |_, this, ()| Ok(this.0));
+        }
+    }
+
+    let ud = lua.create_userdata(Plain)?;
+    let desc = ud.debug_string();
+    assert!(
+        desc.contains(&format!("{:?}", ud.to_pointer())),
+        "unexpected description: {desc}"
+    );
+
+    let ud = lua.create_userdata(Named("widget"))?;
+    let desc = ud.debug_string();
+    assert!(desc.ends_with("(widget)"), "unexpected description: {desc}");
+
+    // Destructed userdata can still be described
+    ud.destroy()?;
+    assert!(!ud.debug_string().is_empty());
+
+    Ok(())
+}
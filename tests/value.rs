@@ -191,6 +191,8 @@ fn test_value_to_string() -> Result<()> {
     impl UserData for MyUserData {}
     let ud: Value = Value::UserData(lua.create_userdata(MyUserData)?);
     assert!(ud.to_string()?.starts_with("MyUserData:"));
+    assert_eq!(ud.userdata_type_name().as_deref(), Some("MyUserData"));
+    assert_eq!(func.userdata_type_name(), None);
 
     let err = Value::Error(Box::new(Error::runtime("test error")));
     assert_eq!(err.to_string()?, "runtime error: test error");
@@ -248,6 +250,14 @@ fn test_value_conversions() -> Result<()> {
     }
     assert_eq!(Value::Integer(1).as_isize(), Some(1isize));
     assert_eq!(Value::Integer(1).as_usize(), Some(1usize));
+    // Floats are never coerced to integers
+    assert!(!Value::Number(42.0).is_integer());
+    assert_eq!(Value::Number(42.0).as_i64(), None);
+    #[cfg(any(feature = "lua54", feature = "lua53"))]
+    {
+        assert!(lua.load("42").eval::<Value>()?.is_integer());
+        assert!(!lua.load("42.0").eval::<Value>()?.is_integer());
+    }
     assert!(Value::Number(1.23).is_number());
     assert_eq!(Value::Number(1.23).as_number(), Some(1.23));
     assert_eq!(Value::Number(1.23).as_f32(), Some(1.23f32));
@@ -312,3 +322,58 @@ fn test_value_exhaustive_match() {
         Value::Other(_) => {}
     }
 }
+
+#[test]
+fn test_transfer_value() -> Result<()> {
+    let lua = Lua::new();
+    let target = Lua::new();
+
+    let value = lua
+        .load(
+            r#"
+        local shared = { 1, 2, 3 }
+        local t = { a = "hello", b = 1.5, c = true, [10] = shared, nested = { shared = shared } }
+        t.self = t
+        return t
+    "#,
+        )
+        .eval::<Value>()?;
+    let copy = lua.transfer_value(&value, &target)?;
+    target.globals().set("t", copy)?;
+    target
+        .load(
+            r#"
+        assert(t.a == "hello" and t.b == 1.5 and t.c == true)
+        assert(#t[10] == 3 and t[10][3] == 3)
+        assert(t[10] == t.nested.shared)
+        assert(t.self == t)
+    "#,
+        )
+        .exec()?;
+
+    // Primitive values are copied as is
+    assert_eq!(
+        lua.transfer_value(&Value::Integer(5), &target)?,
+        Value::Integer(5)
+    );
+
+    // Functions cannot be transferred, even nested inside tables
+    let func = Value::Function(lua.create_function(|_, ()| Ok(()))?);
+    let err = lua.transfer_value(&func, &target).unwrap_err();
+    assert!(err.to_string().contains("cannot transfer function"));
+    let t = lua.create_table_from([("f", func)])?;
+    assert!(lua.transfer_value(&Value::Table(t), &target).is_err());
+
+    // Deeply nested tables are rejected
+    let deep = lua
+        .load("local t = {} for _ = 1, 300 do t = { t } end return t")
+        .eval::<Value>()?;
+    let err = lua.transfer_value(&deep, &target).unwrap_err();
+    assert!(err.to_string().contains("nested deeper"));
+
+    // Transferring into the same instance works as well
+    let copy = lua.transfer_value(&value, &lua)?;
+    assert_ne!(copy, value);
+
+    Ok(())
+}